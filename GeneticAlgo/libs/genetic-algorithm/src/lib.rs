@@ -1,36 +1,144 @@
 use rand::{Rng,RngCore};
+use rand::distributions::WeightedIndex;
 use rand::seq::SliceRandom;
+use rand::distributions::Distribution;
+use serde::{Deserialize, Serialize};
 use std::ops::Index;
 
 pub struct GeneticAlgorithm<S>{
     selection_method: S,
     crossover_method: Box<dyn CrossoverMethod>,
     mutation_method: Box<dyn MutationMethod>,
+    /// Number of best individuals copied unchanged into the next generation
+    elite_count: usize,
+    /// Number of generations evolved so far
+    generation: usize,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Statistics {
     min_fitness: f32,
     max_fitness: f32,
     avg_fitness: f32,
+    median_fitness: f32,
+    std_dev_fitness: f32,
+    /// Index of the fittest individual within the evaluated population
+    best_index: usize,
+    /// Mean euclidean distance between every pair of chromosomes
+    diversity: f32,
+    /// Fitness of every individual, sorted in ascending order
+    sorted_fitness: Vec<f32>,
+}
+
+/// Best chromosomes ever seen, sorted from the fittest one
+#[derive(Clone, Debug)]
+pub struct HallOfFame {
+    capacity: usize,
+    entries: Vec<HallOfFameEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HallOfFameEntry {
+    chromosome: Chromosome,
+    fitness: f32,
+    generation: usize,
 }
 
 pub struct RouletteWheelSelection;
 
 #[derive(Clone, Debug)]
+pub struct TournamentSelection {
+    size: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct RankSelection;
+
+#[derive(Clone, Debug)]
+pub struct StochasticUniversalSampling;
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Chromosome {
     genes: Vec<f32>,
+    /// Per-gene mutation step sizes evolved alongside the genes by
+    /// `SelfAdaptiveMutation`; empty for every other mutation method
+    step_sizes: Vec<f32>,
 }
 
 #[derive(Clone, Debug)]
 pub struct UniformCrossover;
 
+#[derive(Clone, Debug)]
+pub struct SinglePointCrossover;
+
+#[derive(Clone, Debug)]
+pub struct KPointCrossover {
+    points: usize,
+}
+
+/// BLX-alpha: each gene is drawn uniformly from the parents' interval,
+/// extended on both sides by `alpha` times its length
+#[derive(Clone, Debug)]
+pub struct BlendCrossover {
+    alpha: f32,
+}
+
+/// Simulated binary crossover (SBX); a higher distribution index `eta`
+/// keeps children closer to their parents
+#[derive(Clone, Debug)]
+pub struct SimulatedBinaryCrossover {
+    eta: f32,
+}
+
+/// Swaps whole neurons (bias followed by incoming weights) between parents
+#[derive(Clone, Debug)]
+pub struct NeuronCrossover {
+    /// Number of genes of each neuron, in chromosome order
+    neurons: Vec<usize>,
+}
+
 #[derive(Clone, Debug)]
 pub struct GaussianMutation {
     chance: f32,
     coeff: f32,
 }
 
+/// Gaussian mutation whose chance and step size follow a `Schedule` over
+/// generations, e.g. exploring early and fine-tuning late
+#[derive(Clone, Debug)]
+pub struct ScheduledMutation {
+    chance: Schedule,
+    coeff: Schedule,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Schedule {
+    Constant(f32),
+
+    /// `initial * decay^generation`, never going below `min`
+    Exponential { initial: f32, decay: f32, min: f32 },
+
+    /// Goes linearly from `initial` to `last` over `generations`, then stays
+    /// at `last`
+    Linear { initial: f32, last: f32, generations: usize },
+}
+
+/// Evolution-strategy style mutation: every chromosome carries one step
+/// size per gene, which is itself mutated (log-normally) before being used
+#[derive(Clone, Debug)]
+pub struct SelfAdaptiveMutation {
+    initial_step_size: f32,
+    min_step_size: f32,
+}
+
+/// What a mutation method knows about the evolution's progress
+#[derive(Clone, Copy, Debug)]
+pub struct MutationContext<'a> {
+    generation: usize,
+    statistics: &'a Statistics,
+}
+
 pub trait Individual {
     fn fitness(&self) -> f32;
 
@@ -47,6 +155,22 @@ pub trait SelectionMethod {
         ) -> &'a I
     where
         I: Individual;
+
+    /// Selects `count` parents at once; methods which need to see the whole
+    /// mating pool (e.g. stochastic universal sampling) override this
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        (0..count)
+            .map(|_| self.select(rng, population))
+            .collect()
+    }
 }
 
 pub trait CrossoverMethod {
@@ -59,7 +183,40 @@ pub trait CrossoverMethod {
 }
 
 pub trait MutationMethod {
-    fn mutate(&self, rng: &mut dyn RngCore, child:&mut Chromosome);
+    fn mutate(
+        &self,
+        rng: &mut dyn RngCore,
+        child: &mut Chromosome,
+        context: &MutationContext,
+    );
+}
+
+impl<C> CrossoverMethod for Box<C>
+where
+    C: CrossoverMethod + ?Sized,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        (**self).crossover(rng, parent_a, parent_b)
+    }
+}
+
+impl<M> MutationMethod for Box<M>
+where
+    M: MutationMethod + ?Sized,
+{
+    fn mutate(
+        &self,
+        rng: &mut dyn RngCore,
+        child: &mut Chromosome,
+        context: &MutationContext,
+    ) {
+        (**self).mutate(rng, child, context)
+    }
 }
 
 impl<S> GeneticAlgorithm<S> 
@@ -75,11 +232,33 @@ where
             selection_method,
             crossover_method: Box::new(crossover_method),
             mutation_method: Box::new(mutation_method),
+            elite_count: 0,
+            generation: 0,
         }
     }               
 
+    pub fn with_elitism(mut self, elite_count: usize) -> Self {
+        self.elite_count = elite_count;
+        self
+    }
+
+    /// Resumes the generation counter, e.g. when restoring a saved population
+    pub fn with_generation(mut self, generation: usize) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    pub fn elite_count(&self) -> usize {
+        self.elite_count
+    }
+
+    /// Number of generations evolved so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
     pub fn evolve<I>(
-        &self,
+        &mut self,
         rng: &mut dyn RngCore,
         population: &[I]
     ) ->  (Vec<I>, Statistics)
@@ -88,32 +267,143 @@ where
     {
         assert!(!population.is_empty());
 
-        let new_population = (0..population.len())
-            .map(|_| {
-                let parent_a = self
-                    .selection_method
-                    .select(rng,population)
-                    .chromosome();
+        let stats = Statistics::new(population);
+
+        let context = MutationContext {
+            generation: self.generation,
+            statistics: &stats,
+        };
+
+        // Elites survive crossover and mutation untouched, so the best
+        // fitness found so far cannot be lost between generations
+        let elite_count = self.elite_count.min(population.len());
 
-                let parent_b = self
-                    .selection_method
-                    .select(rng,population)
-                    .chromosome();
+        let elites = fittest(population, elite_count)
+            .into_iter()
+            .map(|individual| I::create(individual.chromosome().clone()));
+
+        let offspring_count = population.len() - elite_count;
+
+        let parents = self
+            .selection_method
+            .select_many(rng, population, 2 * offspring_count);
+
+        let offspring: Vec<_> = parents
+            .chunks(2)
+            .map(|parents| {
+                let parent_a = parents[0].chromosome();
+                let parent_b = parents[1].chromosome();
 
                 let mut child = self
                     .crossover_method
                     .crossover(rng, parent_a, parent_b);
 
-                self.mutation_method.mutate(rng, &mut child);
+                child.inherit_step_sizes(parent_a, parent_b);
+
+                self.mutation_method.mutate(rng, &mut child, &context);
 
                 I::create(child)
             })
             .collect();
-        let stats = Statistics::new(population);
+
+        let new_population = elites.chain(offspring).collect();
+
+        self.generation += 1;
         (new_population, stats)
     }
 }
 
+/// Returns the `count` fittest individuals, the fittest one first
+fn fittest<I>(population: &[I], count: usize) -> Vec<&I>
+where
+    I: Individual,
+{
+    let mut sorted: Vec<_> = population.iter().collect();
+    sorted.sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+    sorted.truncate(count);
+    sorted
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Records the fittest individuals of `population`, evaluated during
+    /// `generation`, keeping at most `capacity` entries overall
+    pub fn update<I>(&mut self, population: &[I], generation: usize)
+    where
+        I: Individual,
+    {
+        for individual in fittest(population, self.capacity) {
+            self.insert(HallOfFameEntry {
+                chromosome: individual.chromosome().clone(),
+                fitness: individual.fitness(),
+                generation,
+            });
+        }
+    }
+
+    pub fn insert(&mut self, entry: HallOfFameEntry) {
+        // Elites come back every generation - keep a single entry per
+        // chromosome, remembering its best evaluation
+        if let Some(existing) = self
+            .entries
+            .iter_mut()
+            .find(|existing| existing.chromosome == entry.chromosome)
+        {
+            if entry.fitness > existing.fitness {
+                *existing = entry;
+            }
+        } else {
+            self.entries.push(entry);
+        }
+
+        // Stable sort, so older entries win ties
+        self.entries
+            .sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+        self.entries.truncate(self.capacity);
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn entries(&self) -> &[HallOfFameEntry] {
+        &self.entries
+    }
+
+    /// The all-time fittest chromosome
+    pub fn champion(&self) -> Option<&HallOfFameEntry> {
+        self.entries.first()
+    }
+}
+
+impl HallOfFameEntry {
+    pub fn new(chromosome: Chromosome, fitness: f32, generation: usize) -> Self {
+        Self {
+            chromosome,
+            fitness,
+            generation,
+        }
+    }
+
+    pub fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+
+    pub fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+
 impl SelectionMethod for RouletteWheelSelection {
     fn select<'a, I>(
         &self,
@@ -123,9 +413,205 @@ impl SelectionMethod for RouletteWheelSelection {
     where
         I: Individual,
     {
+        assert!(!population.is_empty());
+
+        // Negative (or NaN) fitness gets no share of the wheel, as with
+        // `StochasticUniversalSampling`
+        let weight = |individual: &I| individual.fitness().max(0.0);
+
+        // When nobody has scored (e.g. with a pessimistic fitness
+        // aggregation) every individual gets an equal share of the wheel
+        if population.iter().all(|individual| weight(individual) == 0.0) {
+            return population.choose(rng).unwrap();
+        }
+
         population
-            .choose_weighted(rng,|individual| individual.fitness())
-            .expect("got an empty population")
+            .choose_weighted(rng, weight)
+            .expect("weights are non-negative and not all zero")
+    }
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+        Self { size }
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+    ) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        // Contestants are drawn with replacement, so a tournament larger
+        // than the population is still well defined
+        (0..self.size)
+            .map(|_| population.choose(rng).expect("got an empty population"))
+            .reduce(|best, contestant| {
+                if contestant.fitness() > best.fitness() {
+                    contestant
+                } else {
+                    best
+                }
+            })
+            .expect("got an empty tournament")
+    }
+}
+
+impl SelectionMethod for RankSelection {
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+    ) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    /// Ranks the population once, then draws every parent from it
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        let ranked = rank(population);
+        let weights = WeightedIndex::new(ranked.iter().map(|(_, rank)| *rank))
+            .expect("got an empty population");
+
+        (0..count)
+            .map(|_| ranked[weights.sample(rng)].0)
+            .collect()
+    }
+}
+
+/// Pairs each individual with its rank, from 1 for the worst one up to
+/// `population.len()` for the best one - only the ordering matters, so zero
+/// or negative fitness is fine. Tied individuals share the average of the
+/// ranks they span.
+fn rank<I>(population: &[I]) -> Vec<(&I, f32)>
+where
+    I: Individual,
+{
+    let mut ranked: Vec<_> = population.iter().map(|individual| (individual, 0.0)).collect();
+    ranked.sort_by(|(a, _), (b, _)| a.fitness().total_cmp(&b.fitness()));
+
+    let mut start = 0;
+
+    while start < ranked.len() {
+        let fitness = ranked[start].0.fitness();
+        let end = start
+            + ranked[start..]
+                .iter()
+                .take_while(|(individual, _)| individual.fitness() == fitness)
+                .count();
+
+        // Average of ranks `start + 1` to `end`
+        let rank = (start + 1 + end) as f32 / 2.0;
+
+        for entry in &mut ranked[start..end] {
+            entry.1 = rank;
+        }
+
+        start = end;
+    }
+
+    ranked
+}
+
+impl SelectionMethod for StochasticUniversalSampling {
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+    ) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let total_fitness: f32 = population
+            .iter()
+            .map(|individual| individual.fitness().max(0.0))
+            .sum();
+
+        // When nobody has scored yet (e.g. in the first generation) every
+        // individual gets an equal share of the wheel
+        let weight = |individual: &I| {
+            if total_fitness > 0.0 {
+                individual.fitness().max(0.0)
+            } else {
+                1.0
+            }
+        };
+
+        let total_weight = if total_fitness > 0.0 {
+            total_fitness
+        } else {
+            population.len() as f32
+        };
+
+        // A single spin places `count` equally spaced pointers on the wheel
+        let distance = total_weight / (count as f32);
+        let start = rng.gen_range(0.0..distance);
+
+        let mut selected = Vec::with_capacity(count);
+        let mut individuals = population.iter();
+        let mut current = individuals.next().unwrap();
+        let mut cumulative = weight(current);
+
+        for i in 0..count {
+            let pointer = start + (i as f32) * distance;
+
+            while cumulative <= pointer {
+                match individuals.next() {
+                    Some(individual) => {
+                        current = individual;
+                        cumulative += weight(current);
+                    }
+
+                    // Floating-point rounding can leave the last pointer
+                    // just past the end of the wheel
+                    None => break,
+                }
+            }
+
+            selected.push(current);
+        }
+
+        // Pointers visit the wheel in order, so shuffle to avoid always
+        // pairing neighbouring individuals during crossover
+        selected.shuffle(rng);
+        selected
     }
 }
 
@@ -141,6 +627,49 @@ impl Chromosome {
     pub fn iter_mut(&mut self) ->  impl Iterator<Item= &mut f32> {
         self.genes.iter_mut()
     }
+
+    pub fn step_sizes(&self) -> &[f32] {
+        &self.step_sizes
+    }
+
+    pub fn with_step_sizes(mut self, step_sizes: Vec<f32>) -> Self {
+        assert!(step_sizes.is_empty() || step_sizes.len() == self.len());
+        self.step_sizes = step_sizes;
+        self
+    }
+
+    /// Crossover methods only mix genes; step sizes are recombined here as
+    /// the mean of both parents' ones
+    fn inherit_step_sizes(&mut self, parent_a: &Chromosome, parent_b: &Chromosome) {
+        let len = self.len();
+
+        if !self.step_sizes.is_empty()
+            || parent_a.step_sizes.len() != len
+            || parent_b.step_sizes.len() != len
+        {
+            return;
+        }
+
+        self.step_sizes = parent_a
+            .step_sizes
+            .iter()
+            .zip(&parent_b.step_sizes)
+            .map(|(a, b)| (a + b) / 2.0)
+            .collect();
+    }
+}
+
+impl Chromosome {
+    /// Euclidean distance between two chromosomes of the same length
+    pub fn distance(&self, other: &Chromosome) -> f32 {
+        assert_eq!(self.len(), other.len());
+
+        self.iter()
+            .zip(other.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
 }
 
 impl Index<usize> for Chromosome {
@@ -155,6 +684,7 @@ impl FromIterator<f32> for Chromosome {
     fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
         Self {
             genes: iter.into_iter().collect(),
+            step_sizes: Vec::new(),
         }
     }
 }
@@ -193,36 +723,337 @@ impl CrossoverMethod for UniformCrossover {
     }
 }
 
-impl GaussianMutation {
-    pub fn new(chance: f32, coeff:f32) -> Self {
-        assert!(chance >=0.0 && chance <= 1.0);
-        Self { chance, coeff }
-    }
-}
-
-impl MutationMethod for GaussianMutation {
-    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
-        for gene in child.iter_mut() {
-            let sign = if rng.gen_bool(0.5) {-1.0} else {1.0};
-            if rng.gen_bool(self.chance as _) {
-                *gene += sign * self.coeff * rng.gen::<f32>();
-            }
-        }
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        KPointCrossover::new(1).crossover(rng, parent_a, parent_b)
     }
-
 }
 
-impl Default for RouletteWheelSelection {
-    fn default() -> Self {
-       Self 
+impl KPointCrossover {
+    pub fn new(points: usize) -> Self {
+        assert!(points > 0);
+        Self { points }
     }
 }
 
-impl Default for UniformCrossover {
-    fn default() -> Self {
-       Self 
-    }
-}
+impl CrossoverMethod for KPointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(),parent_b.len());
+
+        if parent_a.len() < 2 {
+            return parent_a.clone();
+        }
+
+        // Cut points lie strictly between two genes
+        let cuts = self.points.min(parent_a.len() - 1);
+        let mut cuts: Vec<_> = rand::seq::index::sample(rng, parent_a.len() - 1, cuts)
+            .into_iter()
+            .map(|cut| cut + 1)
+            .collect();
+
+        cuts.sort_unstable();
+
+        let mut cuts = cuts.into_iter().peekable();
+        let mut from_a = true;
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .enumerate()
+            .map(|(index, (&a, &b))| {
+                if cuts.next_if_eq(&index).is_some() {
+                    from_a = !from_a;
+                }
+
+                if from_a {a} else {b}
+            })
+            .collect()
+    }
+}
+
+impl BlendCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha >= 0.0);
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for BlendCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(),parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let extent = self.alpha * (a - b).abs();
+                let min = a.min(b) - extent;
+                let max = a.max(b) + extent;
+
+                if min < max {
+                    rng.gen_range(min..=max)
+                } else {
+                    a
+                }
+            })
+            .collect()
+    }
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(eta: f32) -> Self {
+        assert!(eta >= 0.0);
+        Self { eta }
+    }
+}
+
+impl CrossoverMethod for SimulatedBinaryCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(),parent_b.len());
+
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let u = rng.gen::<f32>();
+
+                let beta = if u <= 0.5 {
+                    libm::powf(2.0 * u, exponent)
+                } else {
+                    libm::powf(1.0 / (2.0 * (1.0 - u)), exponent)
+                };
+
+                // SBX yields two symmetric children - keep either of them
+                let sign = if rng.gen_bool(0.5) {-1.0} else {1.0};
+
+                0.5 * ((a + b) + sign * beta * (a - b))
+            })
+            .collect()
+    }
+}
+
+impl NeuronCrossover {
+    pub fn new(neurons: Vec<usize>) -> Self {
+        assert!(neurons.iter().all(|&genes| genes > 0));
+        Self { neurons }
+    }
+}
+
+impl CrossoverMethod for NeuronCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(),parent_b.len());
+        assert_eq!(parent_a.len(), self.neurons.iter().sum::<usize>());
+
+        let mut genes = Vec::with_capacity(parent_a.len());
+        let mut offset = 0;
+
+        for &length in &self.neurons {
+            let parent = if rng.gen_bool(0.5) {parent_a} else {parent_b};
+
+            genes.extend_from_slice(&parent.genes[offset..(offset + length)]);
+            offset += length;
+        }
+
+        genes.into_iter().collect()
+    }
+}
+
+impl GaussianMutation {
+    pub fn new(chance: f32, coeff:f32) -> Self {
+        assert!(chance >=0.0 && chance <= 1.0);
+        Self { chance, coeff }
+    }
+}
+
+impl MutationMethod for GaussianMutation {
+    fn mutate(
+        &self,
+        rng: &mut dyn RngCore,
+        child: &mut Chromosome,
+        _context: &MutationContext,
+    ) {
+        gaussian_mutation(rng, child, self.chance, self.coeff);
+    }
+}
+
+/// Draws from N(0, 1) with the Box-Muller transform.
+///
+/// Goes through `libm` rather than the platform's `ln` / `cos` (which
+/// `rand_distr` relies on), so that a given RNG yields the same numbers on
+/// native and WASM targets.
+pub fn standard_normal(rng: &mut dyn RngCore) -> f32 {
+    // From (0, 1], keeping `ln` finite
+    let u1 = 1.0 - rng.gen::<f32>();
+    let u2 = rng.gen::<f32>();
+
+    (-2.0 * libm::logf(u1)).sqrt() * libm::cosf(2.0 * std::f32::consts::PI * u2)
+}
+
+/// Adds `coeff * N(0, 1)` to each gene with probability `chance`
+fn gaussian_mutation(
+    rng: &mut dyn RngCore,
+    child: &mut Chromosome,
+    chance: f32,
+    coeff: f32,
+) {
+    for gene in child.iter_mut() {
+        if rng.gen_bool(chance as _) {
+            *gene += coeff * standard_normal(rng);
+        }
+    }
+}
+
+impl ScheduledMutation {
+    pub fn new(chance: Schedule, coeff: Schedule) -> Self {
+        Self { chance, coeff }
+    }
+}
+
+impl MutationMethod for ScheduledMutation {
+    fn mutate(
+        &self,
+        rng: &mut dyn RngCore,
+        child: &mut Chromosome,
+        context: &MutationContext,
+    ) {
+        let chance = self.chance.value(context.generation()).clamp(0.0, 1.0);
+        let coeff = self.coeff.value(context.generation());
+
+        gaussian_mutation(rng, child, chance, coeff);
+    }
+}
+
+impl Schedule {
+    pub fn value(&self, generation: usize) -> f32 {
+        match *self {
+            Schedule::Constant(value) => value,
+
+            Schedule::Exponential { initial, decay, min } => {
+                (initial * libm::powf(decay, generation as f32)).max(min)
+            }
+
+            Schedule::Linear { initial, last, generations } => {
+                if generation >= generations {
+                    last
+                } else {
+                    let progress = (generation as f32) / (generations as f32);
+                    initial + progress * (last - initial)
+                }
+            }
+        }
+    }
+}
+
+impl SelfAdaptiveMutation {
+    pub fn new(initial_step_size: f32, min_step_size: f32) -> Self {
+        assert!(initial_step_size > 0.0);
+        assert!(min_step_size >= 0.0);
+
+        Self { initial_step_size, min_step_size }
+    }
+}
+
+impl MutationMethod for SelfAdaptiveMutation {
+    fn mutate(
+        &self,
+        rng: &mut dyn RngCore,
+        child: &mut Chromosome,
+        _context: &MutationContext,
+    ) {
+        let len = child.len();
+
+        if len == 0 {
+            return;
+        }
+
+        if child.step_sizes.len() != len {
+            child.step_sizes = vec![self.initial_step_size; len];
+        }
+
+        // Learning rates recommended by Schwefel
+        let global_rate = 1.0 / (2.0 * len as f32).sqrt();
+        let local_rate = 1.0 / (2.0 * (len as f32).sqrt()).sqrt();
+
+        let global = standard_normal(rng);
+
+        for (gene, step_size) in child.genes.iter_mut().zip(&mut child.step_sizes) {
+            let local = standard_normal(rng);
+
+            *step_size = (*step_size * libm::expf(global_rate * global + local_rate * local))
+                .max(self.min_step_size);
+
+            *gene += *step_size * standard_normal(rng);
+        }
+    }
+}
+
+impl<'a> MutationContext<'a> {
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Statistics of the population the child is bred from
+    pub fn statistics(&self) -> &'a Statistics {
+        self.statistics
+    }
+}
+
+impl Default for RouletteWheelSelection {
+    fn default() -> Self {
+       Self 
+    }
+}
+
+impl Default for RankSelection {
+    fn default() -> Self {
+       Self 
+    }
+}
+
+impl Default for StochasticUniversalSampling {
+    fn default() -> Self {
+       Self 
+    }
+}
+
+impl Default for UniformCrossover {
+    fn default() -> Self {
+       Self 
+    }
+}
+
+impl Default for SinglePointCrossover {
+    fn default() -> Self {
+       Self 
+    }
+}
 
 impl Statistics {
     fn new<I>(population: &[I]) -> Self
@@ -234,19 +1065,68 @@ impl Statistics {
         let mut min_fitness = population[0].fitness();
         let mut max_fitness = min_fitness;
         let mut sum_fitness = 0.0;
+        let mut best_index = 0;
 
-        for individual in population {
+        for (index, individual) in population.iter().enumerate() {
             let fitness = individual.fitness();
 
+            if fitness > max_fitness {
+                best_index = index;
+            }
+
             min_fitness = min_fitness.min(fitness);
             max_fitness = max_fitness.max(fitness);
             sum_fitness += fitness;
         }
 
-        Self {
+        let avg_fitness = sum_fitness / (population.len() as f32);
+
+        let variance = population
+            .iter()
+            .map(|individual| (individual.fitness() - avg_fitness).powi(2))
+            .sum::<f32>()
+            / (population.len() as f32);
+
+        let mut sorted_fitness: Vec<_> = population
+            .iter()
+            .map(|individual| individual.fitness())
+            .collect();
+
+        sorted_fitness.sort_by(f32::total_cmp);
+
+        let mut stats = Self {
             min_fitness,
             max_fitness,
-            avg_fitness: sum_fitness / (population.len() as f32),
+            avg_fitness,
+            median_fitness: 0.0,
+            std_dev_fitness: variance.sqrt(),
+            best_index,
+            diversity: Self::diversity_of(population),
+            sorted_fitness,
+        };
+
+        stats.median_fitness = stats.percentile(50.0);
+        stats
+    }
+
+    fn diversity_of<I>(population: &[I]) -> f32
+    where
+        I: Individual,
+    {
+        let mut sum_distance = 0.0;
+        let mut pairs = 0;
+
+        for (i, a) in population.iter().enumerate() {
+            for b in &population[(i + 1)..] {
+                sum_distance += a.chromosome().distance(b.chromosome());
+                pairs += 1;
+            }
+        }
+
+        if pairs == 0 {
+            0.0
+        } else {
+            sum_distance / (pairs as f32)
         }
     }
 
@@ -261,20 +1141,839 @@ impl Statistics {
     pub fn avg_fitness(&self) -> f32 {
         self.avg_fitness
     }
+
+    pub fn median_fitness(&self) -> f32 {
+        self.median_fitness
+    }
+
+    pub fn std_dev_fitness(&self) -> f32 {
+        self.std_dev_fitness
+    }
+
+    pub fn best_index(&self) -> usize {
+        self.best_index
+    }
+
+    /// Genotypic diversity of the population; a value close to zero means
+    /// the population has converged
+    pub fn diversity(&self) -> f32 {
+        self.diversity
+    }
+
+    /// Fitness below which `percent`% of the population falls, linearly
+    /// interpolated between the closest ranks
+    pub fn percentile(&self, percent: f32) -> f32 {
+        assert!((0.0..=100.0).contains(&percent));
+
+        let rank = percent / 100.0 * ((self.sorted_fitness.len() - 1) as f32);
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        let weight = rank - (lower as f32);
+
+        self.sorted_fitness[lower]
+            + weight * (self.sorted_fitness[upper] - self.sorted_fitness[lower])
+    }
+
+    pub fn percentiles(&self, percents: &[f32]) -> Vec<f32> {
+        percents
+            .iter()
+            .map(|&percent| self.percentile(percent))
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::BTreeMap;
+
+    #[derive(Clone, Debug)]
+    struct TestIndividual {
+        fitness: f32,
+        chromosome: Chromosome,
+    }
+
+    impl TestIndividual {
+        fn new(fitness: f32) -> Self {
+            Self {
+                fitness,
+                chromosome: Chromosome::from_iter(Vec::new()),
+            }
+        }
+    }
+
+    impl Individual for TestIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self {
+                fitness: 0.0,
+                chromosome,
+            }
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+
+        fn fitness(&self) -> f32 {
+            self.fitness
+        }
+    }
+
+    fn population(fitnesses: &[f32]) -> Vec<TestIndividual> {
+        fitnesses.iter().copied().map(TestIndividual::new).collect()
+    }
+
+    /// Counts how many times each fitness value got selected
+    fn histogram(selected: &[&TestIndividual]) -> BTreeMap<i32, usize> {
+        let mut histogram = BTreeMap::new();
+
+        for individual in selected {
+            *histogram.entry(individual.fitness() as i32).or_insert(0) += 1;
+        }
 
-    mod random {
+        histogram
+    }
+
+    mod selection {
         use super::*;
-        use rand::SeedableRng;
-        use rand_chacha::ChaCha8Rng;
 
-        // #[test]
-        // fn test_() {
-        //     todo!();
-        // }
+        mod roulette_wheel {
+            use super::*;
+
+            #[test]
+            fn test_zero_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[0.0, 0.0, 0.0]);
+
+                let selected =
+                    RouletteWheelSelection.select_many(&mut rng, &population, 10);
+
+                assert_eq!(selected.len(), 10);
+            }
+
+            #[test]
+            fn test_negative_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[-3.0, 1.0, 0.0, 3.0]);
+
+                let selected =
+                    RouletteWheelSelection.select_many(&mut rng, &population, 1000);
+
+                let histogram = histogram(&selected);
+
+                // Only the individuals that scored get picked
+                assert_eq!(histogram.keys().copied().collect::<Vec<_>>(), vec![1, 3]);
+                assert!(histogram[&1] < histogram[&3]);
+            }
+        }
+
+        mod tournament {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[2.0, 1.0, 4.0, 3.0]);
+                let method = TournamentSelection::new(2);
+
+                let selected = method.select_many(&mut rng, &population, 1000);
+                let histogram = histogram(&selected);
+
+                // The worst individual can only win a tournament against
+                // itself, the best one wins every tournament it enters
+                assert!(histogram[&1] < histogram[&2]);
+                assert!(histogram[&2] < histogram[&3]);
+                assert!(histogram[&3] < histogram[&4]);
+            }
+
+            #[test]
+            fn test_zero_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[0.0, 0.0, 0.0]);
+                let method = TournamentSelection::new(3);
+
+                let selected = method.select_many(&mut rng, &population, 10);
+
+                assert_eq!(selected.len(), 10);
+            }
+
+            #[test]
+            #[should_panic]
+            fn test_empty_tournament() {
+                TournamentSelection::new(0);
+            }
+        }
+
+        mod rank {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[100.0, 1.0, 4.0, 3.0]);
+                let method = RankSelection;
+
+                let selected = method.select_many(&mut rng, &population, 1000);
+                let histogram = histogram(&selected);
+
+                // Ranks are 4:1:3:2, so the outlier is not overwhelmingly
+                // favoured the way it would be by a roulette wheel
+                assert!(histogram[&1] < histogram[&3]);
+                assert!(histogram[&3] < histogram[&4]);
+                assert!(histogram[&4] < histogram[&100]);
+                assert!(histogram[&100] < 500);
+            }
+
+            #[test]
+            fn test_zero_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[0.0, 0.0, 0.0]);
+                let method = RankSelection;
+
+                let selected = method.select_many(&mut rng, &population, 10);
+
+                assert_eq!(selected.len(), 10);
+            }
+
+            #[test]
+            fn test_ties() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[1.0, 2.0, 5.0, 2.0, 2.0]);
+
+                let ranks: Vec<_> = rank(&population)
+                    .into_iter()
+                    .map(|(individual, rank)| (individual.fitness(), rank))
+                    .collect();
+
+                assert_eq!(
+                    ranks,
+                    [(1.0, 1.0), (2.0, 3.0), (2.0, 3.0), (2.0, 3.0), (5.0, 5.0)],
+                );
+
+                // Identical individuals are picked about as often as each
+                // other
+                let selected = RankSelection.select_many(&mut rng, &population, 3000);
+
+                let counts: Vec<_> = [1, 3, 4]
+                    .iter()
+                    .map(|&id| {
+                        selected
+                            .iter()
+                            .filter(|individual| std::ptr::eq(**individual, &population[id]))
+                            .count()
+                    })
+                    .collect();
+
+                for count in counts {
+                    assert!((500..700).contains(&count), "{}", count);
+                }
+            }
+        }
+
+        mod stochastic_universal_sampling {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[2.0, 1.0, 4.0, 3.0]);
+                let method = StochasticUniversalSampling;
+
+                let selected = method.select_many(&mut rng, &population, 10);
+                let histogram = histogram(&selected);
+
+                // Each individual gets exactly its expected share, give or
+                // take one pointer
+                let expected = BTreeMap::from([(1, 1), (2, 2), (3, 3), (4, 4)]);
+
+                assert_eq!(histogram, expected);
+            }
+
+            #[test]
+            fn test_zero_fitness() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[0.0, 0.0, 0.0, 0.0]);
+                let method = StochasticUniversalSampling;
+
+                let selected = method.select_many(&mut rng, &population, 8);
+                let histogram = histogram(&selected);
+
+                assert_eq!(histogram, BTreeMap::from([(0, 8)]));
+                assert_eq!(selected.len(), 8);
+            }
+
+            #[test]
+            fn test_single() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let population = population(&[0.0, 5.0]);
+                let method = StochasticUniversalSampling;
+
+                for _ in 0..100 {
+                    let selected = method.select(&mut rng, &population);
+
+                    assert_eq!(selected.fitness(), 5.0);
+                }
+            }
+        }
+    }
+
+    mod evolve {
+        use super::*;
+
+        fn individual(genes: &[f32]) -> TestIndividual {
+            TestIndividual::create(genes.iter().copied().collect())
+        }
+
+        #[test]
+        fn test_zero_fitness() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut ga = GeneticAlgorithm::new(
+                TournamentSelection::new(2),
+                UniformCrossover,
+                GaussianMutation::new(0.5, 0.5),
+            );
+
+            let population = vec![
+                individual(&[0.0, 0.0, 0.0]),
+                individual(&[1.0, 1.0, 1.0]),
+                individual(&[1.0, 2.0, 1.0]),
+            ];
+
+            let (new_population, _) = ga.evolve(&mut rng, &population);
+
+            assert_eq!(new_population.len(), population.len());
+        }
+
+        #[test]
+        fn test_elitism() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut ga = GeneticAlgorithm::new(
+                RankSelection,
+                UniformCrossover,
+                GaussianMutation::new(1.0, 10.0),
+            )
+            .with_elitism(2);
+
+            let mut population = vec![
+                individual(&[0.0, 0.0, 0.0]),
+                individual(&[1.0, 1.0, 1.0]),
+                individual(&[2.0, 2.0, 2.0]),
+                individual(&[3.0, 3.0, 3.0]),
+            ];
+
+            population[0].fitness = 1.0;
+            population[1].fitness = 4.0;
+            population[2].fitness = 2.0;
+            population[3].fitness = 3.0;
+
+            let (new_population, _) = ga.evolve(&mut rng, &population);
+
+            assert_eq!(new_population.len(), population.len());
+            assert_eq!(new_population[0].chromosome, population[1].chromosome);
+            assert_eq!(new_population[1].chromosome, population[3].chromosome);
+
+            // Everybody else went through crossover and a (strong) mutation
+            for individual in &new_population[2..] {
+                assert!(population
+                    .iter()
+                    .all(|parent| parent.chromosome != individual.chromosome));
+            }
+        }
+
+        #[test]
+        fn test_elitism_larger_than_population() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut ga = GeneticAlgorithm::new(
+                RouletteWheelSelection,
+                UniformCrossover,
+                GaussianMutation::new(1.0, 10.0),
+            )
+            .with_elitism(5);
+
+            let population = vec![
+                individual(&[0.0, 0.0, 0.0]),
+                individual(&[1.0, 1.0, 1.0]),
+            ];
+
+            let (new_population, _) = ga.evolve(&mut rng, &population);
+
+            assert_eq!(new_population.len(), 2);
+        }
+    }
+
+    mod hall_of_fame {
+        use super::*;
+
+        fn individual(gene: f32, fitness: f32) -> TestIndividual {
+            TestIndividual {
+                fitness,
+                chromosome: Chromosome::from_iter(vec![gene]),
+            }
+        }
+
+        fn summary(hall_of_fame: &HallOfFame) -> Vec<(f32, f32, usize)> {
+            hall_of_fame
+                .entries()
+                .iter()
+                .map(|entry| {
+                    (entry.chromosome()[0], entry.fitness(), entry.generation())
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test() {
+            let mut hall_of_fame = HallOfFame::new(3);
+
+            assert!(hall_of_fame.champion().is_none());
+
+            hall_of_fame.update(
+                &[
+                    individual(1.0, 1.0),
+                    individual(2.0, 5.0),
+                    individual(3.0, 3.0),
+                    individual(4.0, 2.0),
+                ],
+                0,
+            );
+
+            assert_eq!(
+                summary(&hall_of_fame),
+                vec![(2.0, 5.0, 0), (3.0, 3.0, 0), (4.0, 2.0, 0)],
+            );
+
+            hall_of_fame.update(
+                &[
+                    individual(5.0, 4.0),
+                    individual(6.0, 0.0),
+                ],
+                1,
+            );
+
+            assert_eq!(
+                summary(&hall_of_fame),
+                vec![(2.0, 5.0, 0), (5.0, 4.0, 1), (3.0, 3.0, 0)],
+            );
+
+            assert_eq!(hall_of_fame.champion().unwrap().fitness(), 5.0);
+        }
+
+        #[test]
+        fn test_duplicates() {
+            let mut hall_of_fame = HallOfFame::new(3);
+
+            hall_of_fame.update(&[individual(1.0, 2.0)], 0);
+            hall_of_fame.update(&[individual(1.0, 3.0)], 1);
+            hall_of_fame.update(&[individual(1.0, 1.0)], 2);
+
+            assert_eq!(summary(&hall_of_fame), vec![(1.0, 3.0, 1)]);
+        }
+    }
+
+    mod statistics {
+        use super::*;
+
+        fn individual(genes: &[f32], fitness: f32) -> TestIndividual {
+            TestIndividual {
+                fitness,
+                chromosome: genes.iter().copied().collect(),
+            }
+        }
+
+        #[test]
+        fn test() {
+            let population = vec![
+                individual(&[0.0, 0.0], 2.0),
+                individual(&[3.0, 4.0], 9.0),
+                individual(&[0.0, 4.0], 4.0),
+                individual(&[3.0, 0.0], 5.0),
+            ];
+
+            let stats = Statistics::new(&population);
+
+            approx::assert_relative_eq!(stats.min_fitness(), 2.0);
+            approx::assert_relative_eq!(stats.max_fitness(), 9.0);
+            approx::assert_relative_eq!(stats.avg_fitness(), 5.0);
+            approx::assert_relative_eq!(stats.median_fitness(), 4.5);
+            approx::assert_relative_eq!(stats.std_dev_fitness(), 6.5f32.sqrt());
+            assert_eq!(stats.best_index(), 1);
+
+            // Pairwise distances: 5, 4, 3, 3, 4, 5
+            approx::assert_relative_eq!(stats.diversity(), 4.0);
+        }
+
+        #[test]
+        fn test_percentiles() {
+            let population: Vec<_> = (0..=10)
+                .rev()
+                .map(|fitness| individual(&[0.0], fitness as f32))
+                .collect();
+
+            let stats = Statistics::new(&population);
+            let actual = stats.percentiles(&[0.0, 25.0, 50.0, 90.0, 95.0, 100.0]);
+
+            approx::assert_relative_eq!(
+                actual.as_slice(),
+                [0.0, 2.5, 5.0, 9.0, 9.5, 10.0].as_ref(),
+            );
+        }
+
+        #[test]
+        fn test_converged() {
+            let population = vec![
+                individual(&[1.0, 2.0], 3.0),
+                individual(&[1.0, 2.0], 3.0),
+            ];
+
+            let stats = Statistics::new(&population);
+
+            approx::assert_relative_eq!(stats.std_dev_fitness(), 0.0);
+            approx::assert_relative_eq!(stats.diversity(), 0.0);
+            assert_eq!(stats.best_index(), 0);
+        }
+
+        #[test]
+        fn test_single() {
+            let population = vec![individual(&[1.0], 7.0)];
+            let stats = Statistics::new(&population);
+
+            approx::assert_relative_eq!(stats.percentile(10.0), 7.0);
+            approx::assert_relative_eq!(stats.diversity(), 0.0);
+        }
+    }
+
+    mod crossover {
+        use super::*;
+
+        fn parents(len: usize) -> (Chromosome, Chromosome) {
+            let parent_a = (1..=len).map(|gene| gene as f32).collect();
+            let parent_b = (1..=len).map(|gene| -(gene as f32)).collect();
+
+            (parent_a, parent_b)
+        }
+
+        /// Number of times the child switches from one parent to the other
+        fn switches(child: &Chromosome) -> usize {
+            child
+                .genes
+                .windows(2)
+                .filter(|genes| genes[0].signum() != genes[1].signum())
+                .count()
+        }
+
+        fn assert_inherited(child: &Chromosome) {
+            for (index, gene) in child.iter().enumerate() {
+                assert_eq!(gene.abs(), (index + 1) as f32);
+            }
+        }
+
+        #[test]
+        fn test_single_point() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(20);
+
+            for _ in 0..100 {
+                let child = SinglePointCrossover.crossover(&mut rng, &parent_a, &parent_b);
+
+                assert_inherited(&child);
+                assert_eq!(switches(&child), 1);
+                assert!(child[0] > 0.0);
+            }
+        }
+
+        #[test]
+        fn test_k_point() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(20);
+
+            for _ in 0..100 {
+                let child = KPointCrossover::new(3).crossover(&mut rng, &parent_a, &parent_b);
+
+                assert_inherited(&child);
+                assert_eq!(switches(&child), 3);
+            }
+        }
+
+        #[test]
+        fn test_k_point_more_points_than_genes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(4);
+
+            let child = KPointCrossover::new(10).crossover(&mut rng, &parent_a, &parent_b);
+
+            assert_eq!(child.genes, vec![1.0, -2.0, 3.0, -4.0]);
+        }
+
+        #[test]
+        fn test_blend() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a: Chromosome = vec![0.0, 1.0, 5.0].into_iter().collect();
+            let parent_b: Chromosome = vec![1.0, 1.0, 3.0].into_iter().collect();
+
+            let mut outside = 0;
+
+            for _ in 0..1000 {
+                let child = BlendCrossover::new(0.5).crossover(&mut rng, &parent_a, &parent_b);
+
+                assert!((-0.5..=1.5).contains(&child[0]));
+                assert_eq!(child[1], 1.0);
+                assert!((2.0..=6.0).contains(&child[2]));
+
+                if !(0.0..=1.0).contains(&child[0]) {
+                    outside += 1;
+                }
+            }
+
+            // Half of the interval lies outside of the parents' range
+            assert!((400..600).contains(&outside));
+        }
+
+        #[test]
+        fn test_simulated_binary() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a: Chromosome = vec![0.0, 2.0, -1.0].into_iter().collect();
+            let parent_b: Chromosome = vec![1.0, 2.0, 1.0].into_iter().collect();
+
+            let mut sum_spread = [0.0, 0.0];
+
+            for (i, eta) in [2.0, 20.0].into_iter().enumerate() {
+                for _ in 0..1000 {
+                    let child = SimulatedBinaryCrossover::new(eta)
+                        .crossover(&mut rng, &parent_a, &parent_b);
+
+                    // Identical parents always produce an identical child
+                    assert_eq!(child[1], 2.0);
+
+                    // Children are spread around the parents' mean
+                    sum_spread[i] += (child[0] - 0.5).abs();
+                }
+            }
+
+            assert!(sum_spread[1] < sum_spread[0]);
+        }
+
+        #[test]
+        fn test_neuron() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(9);
+            let method = NeuronCrossover::new(vec![3, 3, 2, 1]);
+
+            for _ in 0..100 {
+                let child = method.crossover(&mut rng, &parent_a, &parent_b);
+
+                assert_inherited(&child);
+
+                // Genes of a single neuron always come from the same parent
+                assert_eq!(child[0].signum(), child[1].signum());
+                assert_eq!(child[1].signum(), child[2].signum());
+                assert_eq!(child[3].signum(), child[4].signum());
+                assert_eq!(child[4].signum(), child[5].signum());
+                assert_eq!(child[6].signum(), child[7].signum());
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_neuron_invalid_layout() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(9);
+
+            NeuronCrossover::new(vec![3, 3]).crossover(&mut rng, &parent_a, &parent_b);
+        }
+    }
+
+    mod mutation {
+        use super::*;
+
+        fn chromosome() -> Chromosome {
+            vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect()
+        }
+
+        fn statistics() -> Statistics {
+            Statistics::new(&[TestIndividual::new(1.0)])
+        }
+
+        fn mutate(
+            method: &dyn MutationMethod,
+            rng: &mut dyn RngCore,
+            generation: usize,
+        ) -> Chromosome {
+            let statistics = statistics();
+            let context = MutationContext {
+                generation,
+                statistics: &statistics,
+            };
+
+            let mut child = chromosome();
+            method.mutate(rng, &mut child, &context);
+            child
+        }
+
+        /// Mean absolute change of every gene over many mutations
+        fn mean_change(
+            method: &dyn MutationMethod,
+            rng: &mut dyn RngCore,
+            generation: usize,
+        ) -> f32 {
+            let mut sum = 0.0;
+            let mut count = 0;
+
+            for _ in 0..1000 {
+                let child = mutate(method, rng, generation);
+
+                for (actual, expected) in child.iter().zip(chromosome().iter()) {
+                    sum += (actual - expected).abs();
+                    count += 1;
+                }
+            }
+
+            sum / (count as f32)
+        }
+
+        mod gaussian {
+            use super::*;
+
+            #[test]
+            fn test_zero_chance() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let child = mutate(&GaussianMutation::new(0.0, 0.5), &mut rng, 0);
+
+                assert_eq!(child, chromosome());
+            }
+
+            #[test]
+            fn test_distribution() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+                // For a normal distribution, E|X| = sigma * sqrt(2 / pi)
+                let actual = mean_change(&GaussianMutation::new(1.0, 0.5), &mut rng, 0);
+                let expected = 0.5 * (2.0 / std::f32::consts::PI).sqrt();
+
+                approx::assert_relative_eq!(actual, expected, epsilon = 0.02);
+
+                // Unlike a uniform distribution scaled by `coeff`, a normal
+                // one sometimes goes beyond a single `coeff`
+                let child = (0..100)
+                    .map(|_| mutate(&GaussianMutation::new(1.0, 0.5), &mut rng, 0))
+                    .find(|child| {
+                        child
+                            .iter()
+                            .zip(chromosome().iter())
+                            .any(|(a, b)| (a - b).abs() > 0.5)
+                    });
+
+                assert!(child.is_some());
+            }
+        }
+
+        mod scheduled {
+            use super::*;
+
+            #[test]
+            fn test_schedule() {
+                let exponential = Schedule::Exponential {
+                    initial: 1.0,
+                    decay: 0.5,
+                    min: 0.2,
+                };
+
+                approx::assert_relative_eq!(exponential.value(0), 1.0);
+                approx::assert_relative_eq!(exponential.value(1), 0.5);
+                approx::assert_relative_eq!(exponential.value(2), 0.25);
+                approx::assert_relative_eq!(exponential.value(3), 0.2);
+                approx::assert_relative_eq!(exponential.value(1000), 0.2);
+
+                let linear = Schedule::Linear {
+                    initial: 0.5,
+                    last: 0.1,
+                    generations: 4,
+                };
+
+                approx::assert_relative_eq!(linear.value(0), 0.5);
+                approx::assert_relative_eq!(linear.value(1), 0.4);
+                approx::assert_relative_eq!(linear.value(4), 0.1);
+                approx::assert_relative_eq!(linear.value(10), 0.1);
+
+                approx::assert_relative_eq!(Schedule::Constant(0.3).value(7), 0.3);
+            }
+
+            #[test]
+            fn test_decay() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+                let method = ScheduledMutation::new(
+                    Schedule::Linear { initial: 1.0, last: 0.0, generations: 10 },
+                    Schedule::Exponential { initial: 1.0, decay: 0.5, min: 0.0 },
+                );
+
+                let early = mean_change(&method, &mut rng, 0);
+                let late = mean_change(&method, &mut rng, 5);
+
+                assert!(late < early / 4.0);
+                assert_eq!(mutate(&method, &mut rng, 10), chromosome());
+            }
+        }
+
+        mod self_adaptive {
+            use super::*;
+
+            #[test]
+            fn test() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+                let method = SelfAdaptiveMutation::new(0.1, 0.01);
+
+                let child = mutate(&method, &mut rng, 0);
+
+                assert_eq!(child.step_sizes().len(), child.len());
+                assert!(child.step_sizes().iter().all(|&step| step >= 0.01));
+                assert!(child.step_sizes().iter().any(|&step| step != 0.1));
+                assert_ne!(child.genes, chromosome().genes);
+            }
+
+            #[test]
+            fn test_inheritance() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+                let parent_a = chromosome().with_step_sizes(vec![0.1; 5]);
+                let parent_b = chromosome().with_step_sizes(vec![0.3; 5]);
+
+                let mut child = UniformCrossover.crossover(&mut rng, &parent_a, &parent_b);
+                assert!(child.step_sizes().is_empty());
+
+                child.inherit_step_sizes(&parent_a, &parent_b);
+
+                approx::assert_relative_eq!(child.step_sizes(), [0.2; 5].as_ref());
+            }
+
+            #[test]
+            fn test_evolve() {
+                let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+                let mut ga = GeneticAlgorithm::new(
+                    TournamentSelection::new(2),
+                    UniformCrossover,
+                    SelfAdaptiveMutation::new(0.5, 0.01),
+                );
+
+                let mut population: Vec<_> = (0..10)
+                    .map(|_| TestIndividual::create(chromosome()))
+                    .collect();
+
+                for _ in 0..3 {
+                    population = ga.evolve(&mut rng, &population).0;
+                }
+
+                assert_eq!(ga.generation(), 3);
+
+                for individual in &population {
+                    assert_eq!(individual.chromosome().step_sizes().len(), 5);
+                }
+            }
+        }
     }
 }
@@ -1,15 +1,23 @@
 use rand::{Rng,RngCore};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct Network {
     layers: Vec<Layer>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LayerTopology {
     pub neurons: usize,
 }
 
+/// Function applied to the output of every neuron
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    Relu,
+}
+
 #[derive(Debug)]
 struct Layer{
     neurons: Vec<Neuron>,
@@ -48,6 +56,23 @@ impl Network {
         Self { layers}
     }
 
+    /// Layout of the network, as accepted by `random()` and `from_weights()`
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        let inputs = LayerTopology {
+            neurons: self.layers[0].neurons[0].weights.len(),
+        };
+
+        let layers = self.layers.iter().map(|layer| LayerTopology {
+            neurons: layer.neurons.len(),
+        });
+
+        std::iter::once(inputs).chain(layers).collect()
+    }
+
+    pub fn activation(&self) -> Activation {
+        Activation::Relu
+    }
+
     pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
         use std::iter::once;
 
@@ -58,6 +83,19 @@ impl Network {
             .copied()
     }
 
+    /// Number of weights (bias included) of each neuron, in the same
+    /// order as they are returned by `weights()`
+    pub fn neuron_layout(layers: &[LayerTopology]) -> Vec<usize> {
+        assert!(layers.len() > 1);
+
+        layers
+            .windows(2)
+            .flat_map(|layers| {
+                std::iter::repeat_n(layers[0].neurons + 1, layers[1].neurons)
+            })
+            .collect()
+    }
+
     pub fn from_weights(
         layers: &[LayerTopology],
         weights: impl IntoIterator<Item = f32>,
@@ -197,7 +235,7 @@ mod tests {
                 Layer::new(vec![Neuron::new(0.5, vec![0.6, 0.7, 0.8])]),
             ]);
 
-            let actual = network.weights();
+            let actual: Vec<_> = network.weights().collect();
             let expected = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
 
             approx::assert_relative_eq!(
@@ -220,7 +258,7 @@ mod tests {
             let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
 
             let network = Network::from_weights(layers, weights.clone());
-            let actual: Vec<_> = network.weights();
+            let actual: Vec<_> = network.weights().collect();
 
             approx::assert_relative_eq!(
                 actual.as_slice(),
@@ -228,4 +266,43 @@ mod tests {
             );
         }
     }
+
+    mod neuron_layout {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layers = &[
+                LayerTopology { neurons: 3 },
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 1 },
+            ];
+
+            let layout = Network::neuron_layout(layers);
+            let network = Network::random(
+                &mut rand::thread_rng(),
+                layers,
+            );
+
+            assert_eq!(layout, vec![4, 4, 3]);
+            assert_eq!(layout.iter().sum::<usize>(), network.weights().count());
+        }
+    }
+
+    mod topology {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layers = [
+                LayerTopology { neurons: 3 },
+                LayerTopology { neurons: 5 },
+                LayerTopology { neurons: 2 },
+            ];
+
+            let network = Network::random(&mut rand::thread_rng(), &layers);
+
+            assert_eq!(network.topology(), layers);
+        }
+    }
 }
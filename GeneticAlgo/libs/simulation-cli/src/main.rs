@@ -0,0 +1,310 @@
+use clap::{Parser, ValueEnum};
+use lib_simulation as sim;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Percentiles reported with every generation
+const PERCENTILES: [f32; 4] = [10.0, 25.0, 75.0, 90.0];
+
+/// Trains collector brains without a browser
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Simulation config (.json or .toml); defaults are used if omitted
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Gridded ocean currents (one `x,y,u,v` cell per line) replacing the
+    /// config's
+    #[arg(long, conflicts_with = "resume")]
+    currents: Option<PathBuf>,
+
+    /// Obstacles (one `kind x,y x,y ...` polygon per line) replacing the
+    /// config's
+    #[arg(long, conflicts_with = "resume")]
+    obstacles: Option<PathBuf>,
+
+    /// Population to resume training from (.json or .bin), as written by
+    /// a checkpoint
+    #[arg(long, conflicts_with = "config")]
+    resume: Option<PathBuf>,
+
+    /// Number of generations to run
+    #[arg(long, default_value_t = 100)]
+    generations: usize,
+
+    /// Seed of the run; a random one is picked (and reported) if omitted
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Format of the per-generation statistics printed to stdout
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+
+    /// Saves the population every N generations (0 disables checkpoints)
+    #[arg(long, default_value_t = 0)]
+    checkpoint_every: usize,
+
+    #[arg(long, default_value = "checkpoints")]
+    checkpoint_dir: PathBuf,
+
+    /// Where to export the all-time champion (.json or .bin) once done
+    #[arg(long)]
+    champion: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+}
+
+/// Statistics of a single generation, as printed to stdout
+#[derive(Clone, Debug, Serialize)]
+struct Record {
+    generation: usize,
+    min: f32,
+    max: f32,
+    avg: f32,
+    median: f32,
+    std_dev: f32,
+    p10: f32,
+    p25: f32,
+    p75: f32,
+    p90: f32,
+    best_index: usize,
+    diversity: f32,
+}
+
+fn main() {
+    if let Err(err) = run(Args::parse()) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    eprintln!("seed: {}", seed);
+
+    let (mut sim, mut rng) = match &args.resume {
+        Some(path) => {
+            let snapshot = load_population(path)?;
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let sim = sim::Simulation::from_snapshot(snapshot, &mut rng)?;
+
+            eprintln!(
+                "resumed {} at generation {}",
+                path.display(),
+                sim.generation(),
+            );
+
+            (sim, rng)
+        }
+
+        None => {
+            let mut config = match &args.config {
+                Some(path) => load_config(path)?,
+                None => sim::SimulationConfig::default(),
+            };
+
+            if let Some(path) = &args.currents {
+                config.currents = sim::CurrentsConfig::from_csv(&fs::read_to_string(path)?)?;
+            }
+
+            if let Some(path) = &args.obstacles {
+                config.obstacles.polygons =
+                    sim::ObstacleConfig::from_polygons(&fs::read_to_string(path)?)?;
+            }
+
+            sim::Simulation::from_seed(config, seed)?
+        }
+    };
+
+    if args.checkpoint_every > 0 {
+        fs::create_dir_all(&args.checkpoint_dir)?;
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    if args.format == Format::Csv {
+        writeln!(out, "{}", Record::CSV_HEADER)?;
+    }
+
+    for _ in 0..args.generations {
+        let generation = sim.generation();
+        let stats = sim.train(&mut rng);
+        let record = Record::new(generation, &stats);
+
+        match args.format {
+            Format::Csv => writeln!(out, "{}", record.to_csv())?,
+            Format::Json => writeln!(out, "{}", serde_json::to_string(&record)?)?,
+        }
+
+        out.flush()?;
+
+        if args.checkpoint_every > 0 && sim.generation() % args.checkpoint_every == 0 {
+            let path = args
+                .checkpoint_dir
+                .join(format!("generation-{:05}.json", sim.generation()));
+
+            fs::write(&path, sim.snapshot().to_json()?)?;
+            eprintln!("checkpoint: {}", path.display());
+        }
+    }
+
+    if let Some(path) = &args.champion {
+        match sim.champion_snapshot() {
+            Some(champion) => {
+                if is_binary(path) {
+                    fs::write(path, champion.to_bytes()?)?;
+                } else {
+                    fs::write(path, champion.to_json()?)?;
+                }
+
+                eprintln!("champion: {}", path.display());
+            }
+
+            None => eprintln!("no generation was evaluated, no champion to export"),
+        }
+    }
+
+    Ok(())
+}
+
+fn load_config(path: &Path) -> Result<sim::SimulationConfig, Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+
+    let config: sim::SimulationConfig = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&content)?,
+        _ => serde_json::from_str(&content)?,
+    };
+
+    config.validate()?;
+    Ok(config)
+}
+
+fn load_population(path: &Path) -> Result<sim::PopulationSnapshot, Box<dyn Error>> {
+    let snapshot = if is_binary(path) {
+        sim::PopulationSnapshot::from_bytes(&fs::read(path)?)?
+    } else {
+        sim::PopulationSnapshot::from_json(&fs::read_to_string(path)?)?
+    };
+
+    Ok(snapshot)
+}
+
+fn is_binary(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("bin")
+}
+
+impl Record {
+    const CSV_HEADER: &'static str =
+        "generation,min,max,avg,median,std_dev,p10,p25,p75,p90,best_index,diversity";
+
+    fn new(generation: usize, stats: &sim::Statistics) -> Self {
+        let [p10, p25, p75, p90] = PERCENTILES.map(|percent| stats.percentile(percent));
+
+        Self {
+            generation,
+            min: stats.min_fitness(),
+            max: stats.max_fitness(),
+            avg: stats.avg_fitness(),
+            median: stats.median_fitness(),
+            std_dev: stats.std_dev_fitness(),
+            p10,
+            p25,
+            p75,
+            p90,
+            best_index: stats.best_index(),
+            diversity: stats.diversity(),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.generation,
+            self.min,
+            self.max,
+            self.avg,
+            self.median,
+            self.std_dev,
+            self.p10,
+            self.p25,
+            self.p75,
+            self.p90,
+            self.best_index,
+            self.diversity,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv() {
+        let record = Record {
+            generation: 3,
+            min: 0.0,
+            max: 4.0,
+            avg: 1.5,
+            median: 1.0,
+            std_dev: 0.5,
+            p10: 0.0,
+            p25: 0.5,
+            p75: 2.0,
+            p90: 3.0,
+            best_index: 7,
+            diversity: 12.25,
+        };
+
+        let header = Record::CSV_HEADER.split(',').count();
+        let row = record.to_csv();
+
+        assert_eq!(row, "3,0,4,1.5,1,0.5,0,0.5,2,3,7,12.25");
+        assert_eq!(row.split(',').count(), header);
+    }
+
+    #[test]
+    fn test_load_config() {
+        let dir = std::env::temp_dir().join("simulation-cli-test-load-config");
+        fs::create_dir_all(&dir).unwrap();
+
+        let toml = dir.join("config.toml");
+        fs::write(&toml, "population_size = 12\n\n[eye]\ncells = 5\n").unwrap();
+
+        let json = dir.join("config.json");
+        fs::write(&json, r#"{ "waste_count": 7 }"#).unwrap();
+
+        let config = load_config(&toml).unwrap();
+        assert_eq!(config.population_size, 12);
+        assert_eq!(config.eye.cells, 5);
+
+        let config = load_config(&json).unwrap();
+        assert_eq!(config.population_size, 40);
+        assert_eq!(config.waste_count, 7);
+    }
+
+    #[test]
+    fn test_load_invalid_config() {
+        let dir = std::env::temp_dir().join("simulation-cli-test-load-invalid-config");
+        fs::create_dir_all(&dir).unwrap();
+
+        let toml = dir.join("config.toml");
+        fs::write(&toml, "[eye]
+cells = 0
+").unwrap();
+
+        let err = load_config(&toml).unwrap_err();
+        assert_eq!(err.to_string(), "invalid config: `eye.cells` must be positive");
+    }
+}
@@ -1,18 +1,43 @@
 use lib_simulation as sim;
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
 use wasm_bindgen::prelude::*;
 use serde::Serialize;
 
+/// Percentiles reported with every generation, unless overridden
+const PERCENTILES: [f32; 4] = [10.0, 25.0, 75.0, 90.0];
+
 #[wasm_bindgen]
 pub struct Simulation {
-    rng: ThreadRng,
+    rng: ChaCha8Rng,
     sim: sim::Simulation,
+    percentiles: Vec<f32>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Statistics {
+    pub min: f32,
+    pub max: f32,
+    pub avg: f32,
+    pub median: f32,
+    pub std_dev: f32,
+    pub percentiles: Vec<Percentile>,
+    pub best_index: usize,
+    pub diversity: f32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Percentile {
+    pub percent: f32,
+    pub fitness: f32,
 }
 
 #[derive(Clone, Debug, Serialize)]
 pub struct Sea {
     pub collectors: Vec<Collector>,
     pub wastes: Vec<Waste>,
+    pub ports: Vec<Port>,
+    pub obstacles: Vec<Obstacle>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -20,41 +45,297 @@ pub struct Collector {
     pub x: f32,
     pub y: f32,
     pub rotation: f32,
+    pub fuel: f32,
+    pub cargo: usize,
 }
 
 #[derive(Clone, Debug, Serialize)]
 pub struct Waste {
     pub x: f32,
     pub y: f32,
+    pub category: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Port {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Obstacle {
+    pub kind: sim::ObstacleKind,
+    pub points: Vec<[f32; 2]>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HallOfFameEntry {
+    pub fitness: f32,
+    pub generation: usize,
 }
 
 #[wasm_bindgen]
 impl Simulation {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        let mut rng = thread_rng();
-        let sim = sim::Simulation::random(&mut rng);
+        Self::from_parts(sim::SimulationConfig::default(), thread_rng().gen())
+            .expect("default config is valid")
+    }
+
+    /// Creates a simulation from a (possibly partial) `SimulationConfig`
+    /// object; missing fields take their default values
+    #[wasm_bindgen(js_name = withConfig)]
+    pub fn with_config(config: JsValue) -> Result<Simulation, JsValue> {
+        Self::from_parts(parse_config(config)?, thread_rng().gen())
+    }
+
+    /// Creates a reproducible simulation, playing out exactly as
+    /// `lib_simulation::Simulation::from_seed` does natively with this seed
+    #[wasm_bindgen(js_name = fromSeed)]
+    pub fn from_seed(seed: u64, config: JsValue) -> Result<Simulation, JsValue> {
+        Self::from_parts(parse_config(config)?, seed)
+    }
+
+    pub fn config(&self) -> JsValue {
+        JsValue::from_serde(self.sim.config()).unwrap()
+    }
+
+    /// Replaces the sea's currents with a gridded field, one `x,y,u,v` cell
+    /// per line
+    #[wasm_bindgen(js_name = setCurrentsCsv)]
+    pub fn set_currents_csv(&mut self, csv: &str) -> Result<(), JsValue> {
+        let currents = sim::CurrentsConfig::from_csv(csv).map_err(to_js_error)?;
+
+        self.sim.set_currents(currents);
+        Ok(())
+    }
+
+    /// Replaces the sea's obstacles with polygons, one `kind x,y x,y ...`
+    /// per line
+    #[wasm_bindgen(js_name = setObstacles)]
+    pub fn set_obstacles(&mut self, polygons: &str) -> Result<(), JsValue> {
+        let mut obstacles = self.sim.config().obstacles.clone();
+        obstacles.polygons = sim::ObstacleConfig::from_polygons(polygons).map_err(to_js_error)?;
+
+        self.sim.set_obstacles(obstacles);
+        Ok(())
+    }
+
+    /// Switches to a built-in fitness function, e.g.
+    /// `{ type: "wastes_per_energy" }`, from the next generation on
+    #[wasm_bindgen(js_name = setFitness)]
+    pub fn set_fitness(&mut self, fitness: JsValue) -> Result<(), JsValue> {
+        let fitness = fitness
+            .into_serde()
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        self.sim.set_fitness(fitness);
+        Ok(())
+    }
 
-        Self { rng, sim }
+    /// Sets which percentiles (between 0 and 100) are reported; throws,
+    /// leaving them unchanged, if any of them is not a number
+    #[wasm_bindgen(js_name = setPercentiles)]
+    pub fn set_percentiles(&mut self, percentiles: Vec<f32>) -> Result<(), JsValue> {
+        if let Some(percent) = percentiles.iter().find(|percent| !percent.is_finite()) {
+            return Err(JsValue::from_str(&format!("invalid percentile: {}", percent)));
+        }
+
+        self.percentiles = percentiles
+            .into_iter()
+            .map(|percent| percent.clamp(0.0, 100.0))
+            .collect();
+
+        Ok(())
     }
 
-    pub fn train(&mut self) -> String {
+    pub fn train(&mut self) -> JsValue {
         let stats = self.sim.train(&mut self.rng);
+        let stats = Statistics::new(&stats, &self.percentiles);
 
-        format!(
-            "min={:.2}, max={:.2}, avg={:.2}",
-            stats.min_fitness(),
-            stats.max_fitness(),
-            stats.avg_fitness()
-        )
+        JsValue::from_serde(&stats).unwrap()
     }
     pub fn sea(&self) -> JsValue {
         let sea= Sea::from(self.sim.sea());
         JsValue::from_serde(&sea).unwrap()
     }
 
-    pub fn step(&mut self) {
-        self.sim.step(&mut self.rng);
+    /// Returns the generation's statistics if this step finished it,
+    /// `null` otherwise
+    pub fn step(&mut self) -> JsValue {
+        match self.sim.step(&mut self.rng) {
+            Some(stats) => {
+                let stats = Statistics::new(&stats, &self.percentiles);
+                JsValue::from_serde(&stats).unwrap()
+            }
+            None => JsValue::NULL,
+        }
+    }
+
+    #[wasm_bindgen(js_name = hallOfFame)]
+    pub fn hall_of_fame(&self) -> JsValue {
+        let entries: Vec<_> = self
+            .sim
+            .hall_of_fame()
+            .entries()
+            .iter()
+            .map(|entry| HallOfFameEntry {
+                fitness: entry.fitness(),
+                generation: entry.generation(),
+            })
+            .collect();
+
+        JsValue::from_serde(&entries).unwrap()
+    }
+
+    /// Per-episode scores of every collector of the last generation
+    #[wasm_bindgen(js_name = episodeScores)]
+    pub fn episode_scores(&self) -> JsValue {
+        JsValue::from_serde(self.sim.episode_scores()).unwrap()
+    }
+
+    /// Shows the champion playing a generation in a sea of its own, while
+    /// training is paused; `false` if there is no champion yet
+    #[wasm_bindgen(js_name = replayChampion)]
+    pub fn replay_champion(&mut self) -> bool {
+        self.sim.replay_champion(&mut self.rng)
+    }
+
+    #[wasm_bindgen(js_name = stopReplay)]
+    pub fn stop_replay(&mut self) {
+        self.sim.stop_replay();
+    }
+
+    /// Resumes a population saved by `exportPopulationJson()`
+    #[wasm_bindgen(js_name = importPopulationJson)]
+    pub fn import_population_json(json: &str) -> Result<Simulation, JsValue> {
+        let snapshot = sim::PopulationSnapshot::from_json(json).map_err(to_js_error)?;
+        Self::from_snapshot(snapshot)
+    }
+
+    /// Resumes a population saved by `exportPopulationBinary()`
+    #[wasm_bindgen(js_name = importPopulationBinary)]
+    pub fn import_population_binary(bytes: &[u8]) -> Result<Simulation, JsValue> {
+        let snapshot = sim::PopulationSnapshot::from_bytes(bytes).map_err(to_js_error)?;
+        Self::from_snapshot(snapshot)
+    }
+
+    #[wasm_bindgen(js_name = exportPopulationJson)]
+    pub fn export_population_json(&self) -> Result<String, JsValue> {
+        self.sim.snapshot().to_json().map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = exportPopulationBinary)]
+    pub fn export_population_binary(&self) -> Result<Vec<u8>, JsValue> {
+        self.sim.snapshot().to_bytes().map_err(to_js_error)
+    }
+
+    /// Returns `undefined` if no generation has been evaluated yet
+    #[wasm_bindgen(js_name = exportChampionJson)]
+    pub fn export_champion_json(&self) -> Result<Option<String>, JsValue> {
+        self.sim
+            .champion_snapshot()
+            .map(|champion| champion.to_json())
+            .transpose()
+            .map_err(to_js_error)
+    }
+
+    /// Returns `undefined` if no generation has been evaluated yet
+    #[wasm_bindgen(js_name = exportChampionBinary)]
+    pub fn export_champion_binary(&self) -> Result<Option<Vec<u8>>, JsValue> {
+        self.sim
+            .champion_snapshot()
+            .map(|champion| champion.to_bytes())
+            .transpose()
+            .map_err(to_js_error)
+    }
+
+    /// Replays (like `replayChampion()`) a brain saved by
+    /// `exportChampionJson()`
+    #[wasm_bindgen(js_name = replayBrainJson)]
+    pub fn replay_brain_json(&mut self, json: &str) -> Result<(), JsValue> {
+        let brain = sim::BrainSnapshot::from_json(json).map_err(to_js_error)?;
+
+        self.sim
+            .replay_brain(&brain, &mut self.rng)
+            .map_err(to_js_error)
+    }
+
+    /// Replays (like `replayChampion()`) a brain saved by
+    /// `exportChampionBinary()`
+    #[wasm_bindgen(js_name = replayBrainBinary)]
+    pub fn replay_brain_binary(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let brain = sim::BrainSnapshot::from_bytes(bytes).map_err(to_js_error)?;
+
+        self.sim
+            .replay_brain(&brain, &mut self.rng)
+            .map_err(to_js_error)
+    }
+}
+
+impl Simulation {
+    fn from_snapshot(snapshot: sim::PopulationSnapshot) -> Result<Self, JsValue> {
+        let mut rng = ChaCha8Rng::seed_from_u64(thread_rng().gen());
+        let sim = sim::Simulation::from_snapshot(snapshot, &mut rng)
+            .map_err(to_js_error)?;
+
+        Ok(Self {
+            rng,
+            sim,
+            percentiles: PERCENTILES.to_vec(),
+        })
+    }
+
+    fn from_parts(config: sim::SimulationConfig, seed: u64) -> Result<Self, JsValue> {
+        let (sim, rng) = sim::Simulation::from_seed(config, seed).map_err(to_js_error)?;
+
+        Ok(Self {
+            rng,
+            sim,
+            percentiles: PERCENTILES.to_vec(),
+        })
+    }
+}
+
+fn to_js_error(err: impl std::error::Error) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+/// Deserializes and validates a `SimulationConfig`; `null` and `undefined`
+/// mean defaults
+fn parse_config(config: JsValue) -> Result<sim::SimulationConfig, JsValue> {
+    if config.is_null() || config.is_undefined() {
+        return Ok(sim::SimulationConfig::default());
+    }
+
+    let config: sim::SimulationConfig = config
+        .into_serde()
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    config.validate().map_err(to_js_error)?;
+    Ok(config)
+}
+
+impl Statistics {
+    fn new(stats: &sim::Statistics, percentiles: &[f32]) -> Self {
+        let percentiles = percentiles
+            .iter()
+            .map(|&percent| Percentile {
+                percent,
+                fitness: stats.percentile(percent),
+            })
+            .collect();
+
+        Self {
+            min: stats.min_fitness(),
+            max: stats.max_fitness(),
+            avg: stats.avg_fitness(),
+            median: stats.median_fitness(),
+            std_dev: stats.std_dev_fitness(),
+            percentiles,
+            best_index: stats.best_index(),
+            diversity: stats.diversity(),
+        }
     }
 }
 
@@ -72,7 +353,19 @@ impl From<&sim::Sea> for Sea {
             .map(Waste::from)
             .collect();
 
-        Self { collectors, wastes }
+        let ports = sea
+            .ports()
+            .iter()
+            .map(Port::from)
+            .collect();
+
+        let obstacles = sea
+            .obstacles()
+            .iter()
+            .map(Obstacle::from)
+            .collect();
+
+        Self { collectors, wastes, ports, obstacles }
     }
 }
 
@@ -82,6 +375,8 @@ impl From<&sim::Collector> for Collector {
             x: collector.position().x,
             y: collector.position().y,
             rotation: collector.rotation().angle(),
+            fuel: collector.fuel(),
+            cargo: collector.cargo(),
         }
     }
 }
@@ -91,6 +386,25 @@ impl From<&sim::Waste> for Waste {
         Self {
             x: waste.position().x,
             y: waste.position().y,
+            category: waste.category(),
+        }
+    }
+}
+
+impl From<&sim::Obstacle> for Obstacle {
+    fn from(obstacle: &sim::Obstacle) -> Self {
+        Self {
+            kind: obstacle.kind(),
+            points: obstacle.points().iter().map(|point| [point.x, point.y]).collect(),
+        }
+    }
+}
+
+impl From<&sim::Port> for Port {
+    fn from(port: &sim::Port) -> Self {
+        Self {
+            x: port.position().x,
+            y: port.position().y,
         }
     }
 }
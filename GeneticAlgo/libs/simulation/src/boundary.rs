@@ -0,0 +1,148 @@
+use crate::*;
+
+/// Which edges of the sea a position has just run into
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Edges {
+    pub x: bool,
+    pub y: bool,
+}
+
+impl Edges {
+    pub fn any(&self) -> bool {
+        self.x || self.y
+    }
+}
+
+impl BoundaryConfig {
+    pub fn is_toroidal(&self) -> bool {
+        matches!(self, Self::Toroidal)
+    }
+
+    /// Shortest way from `from` to `to` - around the torus, if the sea is
+    /// one
+    pub fn offset(&self, from: na::Point2<f32>, to: na::Point2<f32>) -> na::Vector2<f32> {
+        let offset = to - from;
+
+        if self.is_toroidal() {
+            na::Vector2::new(offset.x - offset.x.round(), offset.y - offset.y.round())
+        } else {
+            offset
+        }
+    }
+
+    pub fn distance(&self, a: na::Point2<f32>, b: na::Point2<f32>) -> f32 {
+        self.offset(a, b).norm()
+    }
+
+    /// Brings a position that has left the sea back into it: around the
+    /// torus, bounced off the edge, or stopped right at it
+    pub(crate) fn confine(&self, position: &mut na::Point2<f32>) -> Edges {
+        match self {
+            Self::Toroidal => {
+                position.x = na::wrap(position.x, 0.0, 1.0);
+                position.y = na::wrap(position.y, 0.0, 1.0);
+
+                Edges::default()
+            }
+
+            Self::Reflective => Edges {
+                x: reflect(&mut position.x),
+                y: reflect(&mut position.y),
+            },
+
+            Self::Wall { .. } => Edges {
+                x: clamp(&mut position.x),
+                y: clamp(&mut position.y),
+            },
+        }
+    }
+
+    /// Score taken off an episode for running into walls
+    pub fn penalty(&self, metrics: &EpisodeMetrics) -> f32 {
+        match self {
+            Self::Wall { penalty } => penalty * (metrics.wall_hits as f32),
+            _ => 0.0,
+        }
+    }
+}
+
+fn reflect(coord: &mut f32) -> bool {
+    let hit = !(0.0..=1.0).contains(coord);
+
+    if *coord < 0.0 {
+        *coord = -*coord;
+    } else if *coord > 1.0 {
+        *coord = 2.0 - *coord;
+    }
+
+    // In case of a move longer than the sea is wide
+    *coord = coord.clamp(0.0, 1.0);
+    hit
+}
+
+fn clamp(coord: &mut f32) -> bool {
+    let clamped = coord.clamp(0.0, 1.0);
+    let hit = clamped != *coord;
+
+    *coord = clamped;
+    hit
+}
+
+/// Heading of something bouncing off `edges`, as a mirror would reflect it
+pub(crate) fn bounce(rotation: na::Rotation2<f32>, edges: Edges) -> na::Rotation2<f32> {
+    let mut direction = rotation * na::Vector2::y();
+
+    if edges.x {
+        direction.x = -direction.x;
+    }
+
+    if edges.y {
+        direction.y = -direction.y;
+    }
+
+    math::rotation(math::angle_between(na::Vector2::y(), direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_offset() {
+        let a = na::Point2::new(0.05, 0.5);
+        let b = na::Point2::new(0.95, 0.5);
+
+        assert_relative_eq!(BoundaryConfig::Toroidal.distance(a, b), 0.1, epsilon = 1e-6);
+        assert_relative_eq!(BoundaryConfig::Reflective.distance(a, b), 0.9, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_confine() {
+        let mut position = na::Point2::new(1.02, 0.5);
+        assert!(!BoundaryConfig::Toroidal.confine(&mut position).any());
+        assert_relative_eq!(position.x, 0.02, epsilon = 1e-6);
+
+        let mut position = na::Point2::new(1.02, -0.01);
+        let edges = BoundaryConfig::Reflective.confine(&mut position);
+        assert_eq!(edges, Edges { x: true, y: true });
+        assert_relative_eq!(position.x, 0.98, epsilon = 1e-6);
+        assert_relative_eq!(position.y, 0.01, epsilon = 1e-6);
+
+        let mut position = na::Point2::new(0.5, 1.02);
+        let edges = BoundaryConfig::Wall { penalty: 1.0 }.confine(&mut position);
+        assert_eq!(edges, Edges { x: false, y: true });
+        assert_eq!(position, na::Point2::new(0.5, 1.0));
+    }
+
+    #[test]
+    fn test_bounce() {
+        // Heading north-east, off the eastern edge
+        let rotation = na::Rotation2::new(-std::f32::consts::FRAC_PI_4);
+        let rotation = bounce(rotation, Edges { x: true, y: false });
+        let direction = rotation * na::Vector2::y();
+
+        assert_relative_eq!(direction.x, -std::f32::consts::FRAC_1_SQRT_2, epsilon = 1e-6);
+        assert_relative_eq!(direction.y, std::f32::consts::FRAC_1_SQRT_2, epsilon = 1e-6);
+    }
+}
@@ -3,41 +3,64 @@ use crate::*;
 #[derive(Debug)]
 pub struct Brain {
     pub(crate) nn: nn::Network,
+    /// Mutation step sizes inherited from the chromosome, so that they
+    /// survive the round trip through the sea (see `ga::SelfAdaptiveMutation`)
+    pub(crate) step_sizes: Vec<f32>,
 }
 
 impl Brain {
-    pub fn random(rng: &mut dyn RngCore, eye: &Eye) -> Self {
+    pub fn random(rng: &mut dyn RngCore, config: &SimulationConfig) -> Self {
         Self {
-            nn: nn::Network::random(rng, &Self::topology(eye)),
+            nn: nn::Network::random(rng, &Self::topology(config)),
+            step_sizes: Vec::new(),
         }
     }
 
     pub(crate) fn from_chromosome(
         chromosome: ga::Chromosome,
-        eye: &Eye,
+        config: &SimulationConfig,
     ) -> Self {
+        let step_sizes = chromosome.step_sizes().to_vec();
+
         Self {
             nn: nn::Network::from_weights(
-                &Self::topology(eye),
+                &Self::topology(config),
                 chromosome,
             ),
+            step_sizes,
         }
     }
 
     pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
-        self.nn.weights().collect()
+        self.nn
+            .weights()
+            .collect::<ga::Chromosome>()
+            .with_step_sizes(self.step_sizes.clone())
+    }
+
+    /// Number of genes of each neuron, as expected by `ga::NeuronCrossover`
+    pub fn neuron_layout(config: &SimulationConfig) -> Vec<usize> {
+        nn::Network::neuron_layout(&Self::topology(config))
+    }
+
+    /// Number of values fed to the brain at each step: the eye's outputs,
+    /// followed by the enabled senses (see `senses::sense()`)
+    pub(crate) fn inputs(config: &SimulationConfig) -> usize {
+        config.build_eye().outputs() + config.senses.inputs(config)
     }
 
-    fn topology(eye: &Eye) -> [nn::LayerTopology; 3] {
+    pub(crate) fn topology(config: &SimulationConfig) -> [nn::LayerTopology; 3] {
+        let inputs = Self::inputs(config);
+
         [
             // The Input Layer
             nn::LayerTopology {
-                neurons: eye.cells(),
+                neurons: inputs,
             },
 
             // The Hidden Layer
             nn::LayerTopology {
-                neurons: 2 * eye.cells(),
+                neurons: 2 * inputs,
             },
 
             // The Output Layer to control both speed and rotation
@@ -45,6 +68,4 @@ impl Brain {
             nn::LayerTopology { neurons: 2 },
         ]
     }
-
-
 }
@@ -0,0 +1,382 @@
+use crate::*;
+use std::f32::consts::*;
+
+/// The original eye, splitting its field of view into cells and summing up
+/// how close the wastes in each cell are
+#[derive(Debug)]
+pub struct CellEye {
+    fov_range: f32,
+    fov_angle: f32,
+    cells: usize, //to divide the fov_angle in equal parts
+    /// How strongly each waste category shows up
+    visibility: Vec<f32>,
+    /// Whether each category gets its own channel of `cells`
+    per_category: bool,
+    /// Whether other collectors get a channel, and their headings another
+    collectors: bool,
+    headings: bool,
+    /// Whether the eye sees across the edges of the sea
+    boundary: BoundaryConfig,
+}
+
+impl CellEye {
+    fn new(
+        fov_range: f32,
+        fov_angle: f32,
+        cells: usize,
+        visibility: Vec<f32>,
+        boundary: BoundaryConfig,
+    ) -> Self {
+        assert!(fov_range > 0.0);
+        assert!(fov_angle > 0.0);
+        assert!(cells > 0);
+        assert!(!visibility.is_empty());
+
+        Self {
+            fov_range,
+            fov_angle,
+            cells,
+            visibility,
+            per_category: false,
+            collectors: false,
+            headings: false,
+            boundary,
+        }
+    }
+
+    pub fn from_config(
+        config: &EyeConfig,
+        categories: &[WasteCategoryConfig],
+        boundary: BoundaryConfig,
+    ) -> Self {
+        Self {
+            per_category: config.per_category,
+            collectors: config.collectors,
+            headings: config.headings,
+            ..Self::new(
+                config.fov_range,
+                config.fov_angle,
+                config.cells,
+                categories.iter().map(|category| category.visibility).collect(),
+                boundary,
+            )
+        }
+    }
+
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    /// Number of channels, each made of `cells()` values: wastes (one
+    /// channel per category, with `per_category`), then other collectors,
+    /// then their headings
+    pub fn channels(&self) -> usize {
+        let mut channels = if self.per_category {
+            self.visibility.len()
+        } else {
+            1
+        };
+
+        if self.collectors {
+            channels += 1;
+        }
+
+        if self.headings {
+            channels += 1;
+        }
+
+        channels
+    }
+
+    fn process_wastes<'a>(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        wastes: impl Iterator<Item = &'a Waste>,
+        obstacles: &[Obstacle],
+    ) -> Vec<f32> {
+        let mut cells = vec![0.0; self.outputs()];
+        for waste in wastes {
+            let located = self.locate(position, rotation, waste.position, obstacles);
+            let Some((cell, dist)) = located else {
+                continue;
+            };
+
+            let channel = if self.per_category { waste.category } else { 0 };
+
+            //Compute energy required to collect this waste
+            let energy = (self.fov_range - dist) / self.fov_range;
+            cells[channel * self.cells + cell] += energy * self.visibility[waste.category];
+        }
+
+        cells
+    }
+
+    /// Fills the collectors' channel the same way as the wastes' one, and
+    /// the headings' one with the heading of the nearest collector in each
+    /// cell (relative to ours, from -1.0 to 1.0 for -PI to PI)
+    fn process_collectors<'a>(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        others: impl IntoIterator<Item = &'a Collector>,
+        obstacles: &[Obstacle],
+        cells: &mut [f32],
+    ) {
+        if !self.collectors && !self.headings {
+            return;
+        }
+
+        // Channels right after the wastes' ones
+        let first = if self.per_category { self.visibility.len() } else { 1 };
+        let collectors = self.collectors.then_some(first);
+        let headings = self.headings.then_some(first + usize::from(self.collectors));
+
+        let mut nearest = vec![f32::INFINITY; self.cells];
+
+        for other in others {
+            let located = self.locate(position, rotation, other.position, obstacles);
+            let Some((cell, dist)) = located else {
+                continue;
+            };
+
+            if let Some(channel) = collectors {
+                cells[channel * self.cells + cell] += (self.fov_range - dist) / self.fov_range;
+            }
+
+            if let Some(channel) = headings {
+                if dist < nearest[cell] {
+                    nearest[cell] = dist;
+
+                    let heading =
+                        na::wrap(math::angle(other.rotation) - math::angle(rotation), -PI, PI);
+                    cells[channel * self.cells + cell] = heading / PI;
+                }
+            }
+        }
+    }
+
+    /// Cell in which `target` shows up, and how far it is - `None` if it is
+    /// out of sight
+    fn locate(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        target: na::Point2<f32>,
+        obstacles: &[Obstacle],
+    ) -> Option<(usize, f32)> {
+        let vec = self.boundary.offset(position, target);
+
+        //Check if target is in range
+        let dist = vec.norm();
+        if dist >= self.fov_range {
+            return None;
+        }
+
+        //Check if target is in the vision span
+        let angle = math::angle_between(na::Vector2::y(), vec);
+        let angle = angle - math::angle(rotation);
+        let angle = na::wrap(angle, -PI, PI);
+        if angle < -self.fov_angle / 2.0 ||
+           angle > self.fov_angle / 2.0
+        {
+            return None;
+        }
+
+        //Check if target is hidden behind land
+        let land = obstacles.iter().filter(|obstacle| obstacle.is_land());
+        let hidden =
+            obstacle::first_crossing(land, position, position + vec, &self.boundary).is_some();
+        if hidden {
+            return None;
+        }
+
+        //Find corresponding cell index
+        let angle = angle + self.fov_angle / 2.0;
+        let cell = angle / self.fov_angle;
+        let cell = cell * (self.cells as f32);
+        let cell = (cell as usize).min(self.cells - 1);
+
+        Some((cell, dist))
+    }
+}
+
+impl Eye for CellEye {
+    /// The cells of the first channel, followed by the cells of the second
+    /// one, and so on
+    fn outputs(&self) -> usize {
+        self.cells * self.channels()
+    }
+
+    fn process_vision(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        surroundings: &Surroundings,
+    ) -> Vec<f32> {
+        let obstacles = surroundings.obstacles;
+        let wastes = surroundings.wastes_near(position, self.fov_range);
+
+        let mut cells = self.process_wastes(position, rotation, wastes, obstacles);
+
+        self.process_collectors(
+            position,
+            rotation,
+            surroundings.others(),
+            obstacles,
+            &mut cells,
+        );
+
+        cells
+    }
+}
+
+impl Default for CellEye {
+    fn default() -> Self {
+        Self::from_config(
+            &EyeConfig::default(),
+            &WastesConfig::default().categories,
+            BoundaryConfig::default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_channels() {
+        let config = EyeConfig {
+            cells: 3,
+            per_category: true,
+            ..Default::default()
+        };
+
+        let categories = [
+            WasteCategoryConfig::default(),
+            WasteCategoryConfig {
+                visibility: 0.5,
+                ..Default::default()
+            },
+        ];
+
+        let eye = CellEye::from_config(&config, &categories, BoundaryConfig::Toroidal);
+        assert_eq!(eye.outputs(), 6);
+
+        // Right ahead, in the middle cell of each channel
+        let wastes = [
+            Waste::new(na::Point2::new(0.5, 0.6), 0),
+            Waste::new(na::Point2::new(0.5, 0.6), 1),
+        ];
+
+        let surroundings = Surroundings {
+            wastes: &wastes,
+            ..Default::default()
+        };
+
+        let vision = eye.process_vision(
+            na::Point2::new(0.5, 0.5),
+            na::Rotation2::new(0.0),
+            &surroundings,
+        );
+
+        let energy = (0.25 - 0.1) / 0.25;
+        assert_relative_eq!(vision[1], energy, epsilon = 1e-5);
+        assert_relative_eq!(vision[4], 0.5 * energy, epsilon = 1e-5);
+        assert_eq!(vision[0] + vision[2] + vision[3] + vision[5], 0.0);
+    }
+
+    #[test]
+    fn test_across_edges() {
+        let wastes = [Waste::new(na::Point2::new(0.5, 0.05), 0)];
+        let categories = WastesConfig::default().categories;
+
+        let vision = |boundary| {
+            CellEye::from_config(&EyeConfig::default(), &categories, boundary)
+                .process_vision(
+                    na::Point2::new(0.5, 0.95),
+                    na::Rotation2::new(0.0),
+                    &Surroundings {
+                        wastes: &wastes,
+                        ..Default::default()
+                    },
+                )
+                .iter()
+                .sum::<f32>()
+        };
+
+        assert!(vision(BoundaryConfig::Toroidal) > 0.0);
+        assert_eq!(vision(BoundaryConfig::Reflective), 0.0);
+    }
+
+    #[test]
+    fn test_occlusion() {
+        let eye = CellEye::default();
+        let wastes = [Waste::new(na::Point2::new(0.5, 0.7), 0)];
+
+        let mut config = ObstacleConfig {
+            kind: ObstacleKind::Island,
+            points: vec![[0.4, 0.59], [0.6, 0.59], [0.6, 0.61], [0.4, 0.61]],
+        };
+
+        let wall = Obstacle::from_config(&config);
+        config.kind = ObstacleKind::Exclusion;
+        let zone = Obstacle::from_config(&config);
+
+        let vision = |obstacles: &[Obstacle]| {
+            let surroundings = Surroundings {
+                wastes: &wastes,
+                obstacles,
+                ..Default::default()
+            };
+
+            eye.process_vision(
+                na::Point2::new(0.5, 0.5),
+                na::Rotation2::new(0.0),
+                &surroundings,
+            )
+            .iter()
+            .sum::<f32>()
+        };
+
+        assert!(vision(&[]) > 0.0);
+        assert_eq!(vision(&[wall]), 0.0);
+        assert!(vision(&[zone]) > 0.0);
+    }
+
+    #[test]
+    fn test_collectors() {
+        let config = EyeConfig {
+            cells: 3,
+            collectors: true,
+            headings: true,
+            ..Default::default()
+        };
+
+        let categories = WastesConfig::default().categories;
+        let eye = CellEye::from_config(&config, &categories, BoundaryConfig::Toroidal);
+        assert_eq!(eye.outputs(), 9);
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut other = Collector::random(&SimulationConfig::default(), &mut rng);
+        other.position = na::Point2::new(0.5, 0.6);
+        other.rotation = na::Rotation2::new(FRAC_PI_2);
+
+        let surroundings = Surroundings {
+            collectors: std::slice::from_ref(&other),
+            ..Default::default()
+        };
+
+        let vision = eye.process_vision(
+            na::Point2::new(0.5, 0.5),
+            na::Rotation2::new(0.0),
+            &surroundings,
+        );
+
+        assert_eq!(&vision[0..3], &[0.0, 0.0, 0.0]);
+        assert_relative_eq!(vision[4], (0.25 - 0.1) / 0.25, epsilon = 1e-5);
+        assert_relative_eq!(vision[7], 0.5, epsilon = 1e-5);
+    }
+}
@@ -5,17 +5,28 @@ pub struct Collector{
     pub(crate) position: na::Point2<f32>,
     pub(crate) rotation: na::Rotation2<f32>,
     pub(crate) speed: f32,
-    pub(crate) eye: Eye,
+    /// Angular velocity, only used with `PhysicsConfig::enabled`
+    pub(crate) spin: f32,
+    /// Only drained with `FuelConfig::enabled`
+    pub(crate) fuel: f32,
+    /// Mass on board, only used with `CargoConfig::enabled`
+    pub(crate) cargo: usize,
+    /// Number and total value of the wastes on board
+    pub(crate) cargo_wastes: usize,
+    pub(crate) cargo_value: f32,
+    pub(crate) eye: Box<dyn Eye>,
     pub(crate) brain: Brain,
-    /// Number of wastes collected
-    pub(crate) proficiency: usize,
+    /// What the collector did during the current episode
+    pub(crate) metrics: EpisodeMetrics,
+    /// Scores of the episodes played so far, see `evaluation::evaluate()`
+    pub(crate) scores: Vec<f32>,
 }
 
 impl Collector {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::random(rng, &eye);
-        Self::new(eye, brain, rng)
+    pub fn random(config: &SimulationConfig, rng: &mut dyn RngCore) -> Self {
+        let eye = config.build_eye();
+        let brain = Brain::random(rng, config);
+        Self::new(config, eye, brain, rng)
     }
 
     pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
@@ -24,11 +35,25 @@ impl Collector {
 
     pub(crate) fn from_chromosome(
         chromosome: ga::Chromosome,
+        config: &SimulationConfig,
         rng: &mut dyn RngCore,
     ) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::from_chromosome(chromosome, &eye);
-        Self::new(eye, brain, rng)
+        let eye = config.build_eye();
+        let brain = Brain::from_chromosome(chromosome, config);
+        Self::new(config, eye, brain, rng)
+    }
+
+    /// Puts the collector back at a random spot, as if freshly spawned
+    pub(crate) fn respawn(&mut self, config: &SimulationConfig, rng: &mut dyn RngCore) {
+        self.position = rng.gen();
+        self.rotation = math::random_rotation(rng);
+        self.speed = config.collector.speed_initial;
+        self.spin = 0.0;
+        self.fuel = config.fuel.capacity;
+        self.cargo = 0;
+        self.cargo_wastes = 0;
+        self.cargo_value = 0.0;
+        self.metrics = EpisodeMetrics::default();
     }
 
     pub fn position(&self) -> na::Point2<f32> {
@@ -39,14 +64,59 @@ impl Collector {
         self.rotation
     }
 
-    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
+    pub fn fuel(&self) -> f32 {
+        self.fuel
+    }
+
+    pub fn cargo(&self) -> usize {
+        self.cargo
+    }
+
+    /// Whether the collector has to unload before picking anything else
+    pub(crate) fn is_full(&self, config: &SimulationConfig) -> bool {
+        config.cargo.enabled && self.cargo >= config.cargo.capacity
+    }
+
+    /// Whether there is room left on board for a waste of `mass`
+    pub(crate) fn can_carry(&self, mass: usize, config: &SimulationConfig) -> bool {
+        !config.cargo.enabled || self.cargo + mass <= config.cargo.capacity
+    }
+
+    /// Unable to move or turn, having run out of fuel
+    pub(crate) fn is_stranded(&self, config: &SimulationConfig) -> bool {
+        config.fuel.enabled && self.fuel <= 0.0
+    }
+
+    /// Drains the fuel tank, if there is a fuel model
+    pub(crate) fn burn(&mut self, energy: f32, config: &SimulationConfig) {
+        if config.fuel.enabled {
+            self.fuel = (self.fuel - energy).max(0.0);
+        }
+    }
+
+    pub fn metrics(&self) -> &EpisodeMetrics {
+        &self.metrics
+    }
+
+    fn new(
+        config: &SimulationConfig,
+        eye: Box<dyn Eye>,
+        brain: Brain,
+        rng: &mut dyn RngCore,
+    ) -> Self {
         Self {
             position: rng.gen(),
-            rotation: rng.gen(),
-            speed: 0.002,
+            rotation: math::random_rotation(rng),
+            speed: config.collector.speed_initial,
+            spin: 0.0,
+            fuel: config.fuel.capacity,
+            cargo: 0,
+            cargo_wastes: 0,
+            cargo_value: 0.0,
             eye,
             brain,
-            proficiency: 0,
+            metrics: EpisodeMetrics::default(),
+            scores: Vec::new(),
         }
     }
 }
@@ -22,14 +22,19 @@ impl ga::Individual for CollectorIndividual {
 }
 
 impl CollectorIndividual {
-    pub fn from_collector(collector: &Collector) -> Self {
+    /// Expects the collector to have been through `evaluation::evaluate()`
+    pub fn from_collector(collector: &Collector, config: &SimulationConfig) -> Self {
         Self {
-            fitness: collector.proficiency as f32,
+            fitness: config.episodes.aggregation.aggregate(&collector.scores),
             chromosome: collector.as_chromosome(),
         }
     }
 
-    pub fn into_collector(self, rng: &mut dyn RngCore) -> Collector {
-        Collector::from_chromosome(self.chromosome, rng)
+    pub fn into_collector(
+        self,
+        config: &SimulationConfig,
+        rng: &mut dyn RngCore,
+    ) -> Collector {
+        Collector::from_chromosome(self.chromosome, config, rng)
     }
 }
@@ -0,0 +1,1013 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::*;
+
+/// Every tunable parameter of a simulation; defaults reproduce the original
+/// hard-coded behavior, and any field missing from a config file falls back
+/// to its default
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    /// Number of collectors in the sea
+    pub population_size: usize,
+
+    /// Number of wastes floating in the sea at the start of an episode
+    pub waste_count: usize,
+
+    /// Number of steps for each generation
+    pub generation_length: usize,
+
+    /// Distance under which a collector picks up a waste
+    pub collection_radius: f32,
+
+    pub collector: CollectorConfig,
+    pub eye: EyeConfig,
+    pub ga: GeneticAlgorithmConfig,
+
+    /// How wastes near a collector are looked up by vision and collisions;
+    /// both strategies give identical results
+    pub spatial_index: SpatialIndexConfig,
+
+    /// Where the population's fitness comes from
+    pub evaluation: EvaluationConfig,
+
+    pub episodes: EpisodesConfig,
+
+    /// Score of a single episode
+    pub fitness: FitnessConfig,
+
+    pub fuel: FuelConfig,
+    pub cargo: CargoConfig,
+    pub physics: PhysicsConfig,
+    pub ports: PortsConfig,
+    pub currents: CurrentsConfig,
+    pub wastes: WastesConfig,
+    pub obstacles: ObstaclesConfig,
+    pub boundary: BoundaryConfig,
+    pub separation: SeparationConfig,
+
+    /// What collectors know about themselves, on top of what they see
+    pub senses: SensesConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollectorConfig {
+    pub speed_min: f32,
+    pub speed_max: f32,
+
+    /// Speed of a freshly spawned collector
+    pub speed_initial: f32,
+
+    /// Maximum change of speed per step
+    pub speed_accel: f32,
+
+    /// Maximum change of rotation per step, in radians
+    pub rotation_accel: f32,
+
+    /// Energy spent to travel a distance of 1 (a full map width)
+    pub energy_per_distance: f32,
+
+    /// Energy spent to turn by 1 radian
+    pub energy_per_radian: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EyeConfig {
+    /// Range of the field of view (1 -> full map width, 0 -> nothing)
+    pub fov_range: f32,
+
+    /// Angle of the field of view, in radians
+    pub fov_angle: f32,
+
+    /// Number of directions the eye is looking to (cells or rays)
+    pub cells: usize,
+
+    /// How far from perfect the eye's sight is
+    pub noise: NoiseConfig,
+
+    /// Kind of eye; the options below only apply to the cell-sum one
+    pub sensor: SensorConfig,
+
+    /// Whether each waste category gets its own set of cells (instead of
+    /// all of them adding up into a single one)
+    pub per_category: bool,
+
+    /// Whether other collectors get a set of cells of their own
+    pub collectors: bool,
+
+    /// Whether the heading of the nearest collector in each cell gets a set
+    /// of cells of its own
+    pub headings: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SensorConfig {
+    /// Sums up how close the wastes in each cell are, see `CellEye`
+    #[default]
+    Cells,
+
+    /// Nearest hit along each ray, one channel per entry, see `RayEye`
+    Rays {
+        #[serde(default = "RayChannelConfig::all")]
+        channels: Vec<RayChannelConfig>,
+
+        /// Radius of the disc other collectors show up as
+        #[serde(default = "SensorConfig::collector_radius")]
+        collector_radius: f32,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RayChannelConfig {
+    pub class: EntityClass,
+
+    #[serde(default)]
+    pub normalization: NormalizationConfig,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityClass {
+    Waste,
+    Collector,
+    Obstacle,
+    Port,
+}
+
+/// Imperfections of an eye, all of which are off by default; they are
+/// drawn from the simulation's RNG, so seeded runs stay reproducible
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseConfig {
+    /// Standard deviation of the Gaussian noise added to each of the eye's
+    /// outputs
+    pub std_dev: f32,
+
+    /// Chance of spotting a waste at the edge of the field of view, rising
+    /// linearly up to 1.0 right next to the collector
+    pub detection: f32,
+
+    /// Chance, each step, of the eye seeing nothing at all
+    pub dropout: f32,
+
+    /// From 0.0 (calm) to 1.0 (rough); waves hide wastes further away than
+    /// `(1.0 - sea_state) * fov_range`
+    pub sea_state: f32,
+}
+
+/// How the distance to a ray's nearest hit is fed to the brain
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NormalizationConfig {
+    /// From 1.0 (right there) down to 0.0 (out of range, or nothing hit)
+    #[default]
+    Proximity,
+
+    /// From 0.0 (right there) up to 1.0 (out of range, or nothing hit)
+    Distance,
+
+    /// Distance as is, `fov_range` when nothing was hit
+    Raw,
+
+    /// `exp(-distance / scale)`, 0.0 when nothing was hit
+    Exponential { scale: f32 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FuelConfig {
+    /// Without a fuel model collectors move for free (their energy is
+    /// still measured); with one, they burn their energy as fuel, stop when
+    /// running out of it, and sense how much of it is left (see
+    /// `SensesConfig`)
+    pub enabled: bool,
+
+    /// Fuel of a freshly spawned collector, in units of energy
+    pub capacity: f32,
+
+    /// Fuel regained per step while docked at a port
+    pub refuel_rate: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsConfig {
+    /// Without a physics model collectors change their speed and heading
+    /// right away, within `CollectorConfig`'s limits; with one, their brain
+    /// works a throttle and a rudder (both from -1.0 to 1.0), and speed and
+    /// spin follow through inertia and drag
+    pub enabled: bool,
+
+    /// Slows down every change of speed and spin
+    pub mass: f32,
+
+    /// Force pushing the collector forward at full throttle
+    pub thrust: f32,
+
+    /// Force holding the collector back, per unit of speed; top speed is
+    /// `thrust / linear_drag`
+    pub linear_drag: f32,
+
+    /// How quickly the spin follows the rudder
+    pub angular_drag: f32,
+
+    /// Spin at full rudder, in radians per unit of time
+    pub max_turn_rate: f32,
+
+    /// Time covered by a single step
+    pub timestep: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CargoConfig {
+    /// Without a cargo model collectors hold any number of wastes, which
+    /// count toward fitness as soon as picked up; with one, wastes only
+    /// count once unloaded at a port, and collectors sense their load and
+    /// the nearest port (see `SensesConfig`)
+    pub enabled: bool,
+
+    /// Mass of wastes (see `WasteCategoryConfig::mass`) a collector can
+    /// hold before it has to unload
+    pub capacity: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortsConfig {
+    /// Locations of the ports, within the same unit square as the sea
+    pub positions: Vec<[f32; 2]>,
+
+    /// Distance under which a collector is docked at a port
+    pub radius: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneticAlgorithmConfig {
+    pub selection: SelectionConfig,
+    pub crossover: CrossoverConfig,
+    pub mutation: MutationConfig,
+
+    /// Number of best collectors carried unchanged into the next generation
+    /// (none by default)
+    pub elite_count: usize,
+
+    /// Number of all-time best brains remembered by the hall of fame
+    pub hall_of_fame_size: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SelectionConfig {
+    RouletteWheel,
+    Tournament { size: usize },
+    Rank,
+    StochasticUniversalSampling,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CrossoverConfig {
+    Uniform,
+    SinglePoint,
+    KPoint { points: usize },
+    Blend { alpha: f32 },
+    SimulatedBinary { eta: f32 },
+
+    /// Swaps whole neurons, following the brain's topology
+    Neuron,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MutationConfig {
+    Gaussian { chance: f32, coeff: f32 },
+    Scheduled { chance: ga::Schedule, coeff: ga::Schedule },
+    SelfAdaptive { initial_step_size: f32, min_step_size: f32 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SpatialIndexConfig {
+    /// Checks every waste, kept around as a reference for testing
+    BruteForce,
+
+    /// Uniform grid of `cells` x `cells` buckets over the sea
+    Grid { cells: usize },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EpisodesConfig {
+    /// Number of episodes (each with its own waste layout and start
+    /// positions) every collector is judged on
+    pub count: usize,
+
+    /// How the per-episode scores are turned into a single fitness
+    pub aggregation: AggregationConfig,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AggregationConfig {
+    #[default]
+    Mean,
+    Min,
+
+    /// Linearly interpolated, `quantile` ranging from 0.0 (min) to 1.0 (max)
+    Quantile { quantile: f32 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WastesConfig {
+    /// Where wastes appear, initially and when respawned
+    pub distribution: DistributionConfig,
+
+    /// What becomes of collected wastes
+    pub respawn: RespawnConfig,
+
+    /// River mouths, steadily emitting new wastes
+    pub rivers: Vec<RiverConfig>,
+
+    /// Rivers stop emitting while the sea holds this many wastes
+    pub max_count: usize,
+
+    /// Kinds of wastes floating around, spawned in proportion to their
+    /// `share`
+    pub categories: Vec<WasteCategoryConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WasteCategoryConfig {
+    /// E.g. `microplastic`, `net`, `bottle`; only used for display
+    pub name: String,
+
+    /// Relative frequency among spawned wastes
+    pub share: f32,
+
+    /// Worth of a single waste, as counted by fitness functions
+    pub value: f32,
+
+    /// Distance from which collectors pick it up, `collection_radius` if
+    /// unset
+    pub radius: Option<f32>,
+
+    /// Room taken on board, in units of `CargoConfig::capacity`
+    pub mass: usize,
+
+    /// How strongly it shows up in the eye (0 -> invisible); the ray eye
+    /// only sees it within `visibility * fov_range`
+    pub visibility: f32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DistributionConfig {
+    #[default]
+    Uniform,
+
+    /// Garbage patches, picked in proportion to their weight
+    Clusters { clusters: Vec<ClusterConfig> },
+
+    /// Density (not necessarily normalized) of a `width` x `height` grid
+    /// covering the sea, stored row by row
+    Raster { width: usize, height: usize, density: Vec<f32> },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    pub x: f32,
+    pub y: f32,
+    pub std_dev: f32,
+    pub weight: f32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RespawnConfig {
+    /// Collected wastes reappear elsewhere right away, keeping the count
+    /// constant
+    #[default]
+    Immediate,
+
+    /// Collected wastes reappear one after another, at `per_step` wastes
+    /// per step
+    RateLimited { per_step: f32 },
+
+    /// Finite supply: collected wastes are gone for the rest of the episode
+    None,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RiverConfig {
+    pub x: f32,
+    pub y: f32,
+
+    /// Wastes emitted per step
+    pub rate: f32,
+
+    /// Standard deviation of the wastes' distance to the mouth
+    pub spread: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeparationConfig {
+    /// Without separation collectors pass through each other; with it,
+    /// collectors coming closer than `radius` collide and are pushed apart
+    pub enabled: bool,
+    pub radius: f32,
+}
+
+/// Inputs fed to the brain after the eye's, each of which can be turned on
+/// or off on its own (e.g. for ablation studies)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensesConfig {
+    /// Remaining fuel, with a fuel model
+    pub fuel: bool,
+
+    /// Mass of the load, with a cargo model
+    pub cargo: bool,
+
+    /// Direction of and distance to the nearest port; `None` feeds them
+    /// with a cargo model only
+    pub port: Option<bool>,
+
+    /// Speed, from 0.0 at `speed_min` to 1.0 at `speed_max` (or, with a
+    /// physics model, from a standstill to its top speed)
+    pub speed: bool,
+
+    /// Heading, as its sine and cosine
+    pub heading: bool,
+
+    /// Position within the sea, as its x and y
+    pub position: bool,
+
+    /// Share of the generation left, from 1.0 down to 0.0
+    pub time: bool,
+}
+
+/// What happens at the edges of the sea
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BoundaryConfig {
+    /// Leaving the sea through an edge brings one back through the opposite
+    /// one
+    #[default]
+    Toroidal,
+
+    /// Collectors bounce off the edges, wastes drift back
+    Reflective,
+
+    /// Edges stop collectors, taking `penalty` off their score each time
+    Wall { penalty: f32 },
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObstaclesConfig {
+    /// Islands, coastlines and exclusion zones, see `Obstacle`
+    pub polygons: Vec<ObstacleConfig>,
+
+    /// What collectors do when bumping into an obstacle
+    pub collision: CollisionConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ObstacleConfig {
+    pub kind: ObstacleKind,
+
+    /// Outline of the polygon, in the sea's coordinates
+    pub points: Vec<[f32; 2]>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObstacleKind {
+    #[default]
+    Island,
+    Coastline,
+
+    /// Protected area, closed to collectors but not to wastes (nor to the
+    /// eye)
+    Exclusion,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CollisionConfig {
+    /// Halt right before the obstacle
+    #[default]
+    Stop,
+
+    /// Keep going along the obstacle's edge
+    Slide,
+}
+
+/// Velocity field drifting wastes and collectors, in distance per step;
+/// gyres and eddies may change over the course of an episode
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CurrentsConfig {
+    #[default]
+    None,
+
+    Uniform { u: f32, v: f32 },
+
+    /// `count` x `count` pairs of counter-rotating gyres, flowing at up to
+    /// `strength`; with a `period` (in steps), they slow down, reverse and
+    /// come back to full strength over and over
+    Gyres {
+        count: usize,
+        strength: f32,
+        #[serde(default)]
+        period: Option<f32>,
+    },
+
+    /// Vortices scattered (by `seed`) around the sea, flowing at up to
+    /// `strength`, `radius` away from their center; each center drifts
+    /// `drift` per step, in a direction of its own
+    Eddies {
+        count: usize,
+        radius: f32,
+        strength: f32,
+        seed: u64,
+        #[serde(default)]
+        drift: f32,
+    },
+
+    /// Gridded field, usually loaded with `CurrentsConfig::from_csv()`;
+    /// `u` and `v` are stored row by row
+    Grid { width: usize, height: usize, u: Vec<f32>, v: Vec<f32> },
+}
+
+/// Built-in fitness functions, see `FitnessFunction`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FitnessConfig {
+    #[default]
+    WastesCollected,
+    WastesPerEnergy,
+    EarlyPickup,
+    Weighted(Weighted),
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EvaluationConfig {
+    /// Every collector lives in the one (visible) sea
+    #[default]
+    Shared,
+
+    /// Collectors are split into batches of `batch_size`, each evaluated
+    /// in a sea of its own - in parallel with the `parallel` feature
+    Isolated { batch_size: usize },
+}
+
+impl SimulationConfig {
+    /// Stable fingerprint of this config (FNV-1a of its JSON form), stored
+    /// alongside saved brains to tell which experiment produced them
+    pub fn hash(&self) -> u64 {
+        let json = serde_json::to_vec(self).expect("config is serializable");
+
+        json.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+            (hash ^ (byte as u64)).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    pub(crate) fn genetic_algorithm(&self) -> ga::GeneticAlgorithm<SelectionConfig> {
+        let layout = Brain::neuron_layout(self);
+
+        ga::GeneticAlgorithm::new(
+            self.ga.selection.clone(),
+            self.ga.crossover.build(layout),
+            self.ga.mutation.build(),
+        )
+        .with_elitism(self.ga.elite_count)
+    }
+}
+
+impl ga::SelectionMethod for SelectionConfig {
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+    ) -> &'a I
+    where
+        I: ga::Individual,
+    {
+        match self {
+            Self::RouletteWheel => {
+                ga::RouletteWheelSelection.select(rng, population)
+            }
+            Self::Tournament { size } => {
+                ga::TournamentSelection::new(*size).select(rng, population)
+            }
+            Self::Rank => ga::RankSelection.select(rng, population),
+            Self::StochasticUniversalSampling => {
+                ga::StochasticUniversalSampling.select(rng, population)
+            }
+        }
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: ga::Individual,
+    {
+        match self {
+            Self::StochasticUniversalSampling => {
+                ga::StochasticUniversalSampling.select_many(rng, population, count)
+            }
+            _ => (0..count)
+                .map(|_| self.select(rng, population))
+                .collect(),
+        }
+    }
+}
+
+impl FitnessFunction for FitnessConfig {
+    fn score(&self, metrics: &EpisodeMetrics) -> f32 {
+        match self {
+            Self::WastesCollected => WastesCollected.score(metrics),
+            Self::WastesPerEnergy => WastesPerEnergy.score(metrics),
+            Self::EarlyPickup => EarlyPickup.score(metrics),
+            Self::Weighted(weighted) => weighted.score(metrics),
+        }
+    }
+}
+
+impl AggregationConfig {
+    pub fn aggregate(&self, scores: &[f32]) -> f32 {
+        assert!(!scores.is_empty());
+
+        match *self {
+            Self::Mean => scores.iter().sum::<f32>() / (scores.len() as f32),
+            Self::Min => scores.iter().copied().fold(f32::INFINITY, f32::min),
+            Self::Quantile { quantile } => {
+                assert!((0.0..=1.0).contains(&quantile));
+
+                let mut scores = scores.to_vec();
+                scores.sort_by(|a, b| a.total_cmp(b));
+
+                let rank = quantile * ((scores.len() - 1) as f32);
+                let lower = rank.floor() as usize;
+                let upper = rank.ceil() as usize;
+                let weight = rank - (lower as f32);
+
+                scores[lower] + weight * (scores[upper] - scores[lower])
+            }
+        }
+    }
+}
+
+impl CrossoverConfig {
+    fn build(&self, neuron_layout: Vec<usize>) -> Box<dyn ga::CrossoverMethod> {
+        match *self {
+            Self::Uniform => Box::new(ga::UniformCrossover),
+            Self::SinglePoint => Box::new(ga::SinglePointCrossover),
+            Self::KPoint { points } => Box::new(ga::KPointCrossover::new(points)),
+            Self::Blend { alpha } => Box::new(ga::BlendCrossover::new(alpha)),
+            Self::SimulatedBinary { eta } => {
+                Box::new(ga::SimulatedBinaryCrossover::new(eta))
+            }
+            Self::Neuron => Box::new(ga::NeuronCrossover::new(neuron_layout)),
+        }
+    }
+}
+
+impl MutationConfig {
+    fn build(&self) -> Box<dyn ga::MutationMethod> {
+        match *self {
+            Self::Gaussian { chance, coeff } => {
+                Box::new(ga::GaussianMutation::new(chance, coeff))
+            }
+            Self::Scheduled { chance, coeff } => {
+                Box::new(ga::ScheduledMutation::new(chance, coeff))
+            }
+            Self::SelfAdaptive { initial_step_size, min_step_size } => {
+                Box::new(ga::SelfAdaptiveMutation::new(
+                    initial_step_size,
+                    min_step_size,
+                ))
+            }
+        }
+    }
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            population_size: 40,
+            waste_count: 60,
+            generation_length: 2500,
+            collection_radius: 0.01,
+            collector: CollectorConfig::default(),
+            eye: EyeConfig::default(),
+            ga: GeneticAlgorithmConfig::default(),
+            spatial_index: SpatialIndexConfig::default(),
+            evaluation: EvaluationConfig::default(),
+            episodes: EpisodesConfig::default(),
+            fitness: FitnessConfig::default(),
+            fuel: FuelConfig::default(),
+            cargo: CargoConfig::default(),
+            physics: PhysicsConfig::default(),
+            ports: PortsConfig::default(),
+            currents: CurrentsConfig::default(),
+            wastes: WastesConfig::default(),
+            obstacles: ObstaclesConfig::default(),
+            boundary: BoundaryConfig::default(),
+            separation: SeparationConfig::default(),
+            senses: SensesConfig::default(),
+        }
+    }
+}
+
+impl Default for CollectorConfig {
+    fn default() -> Self {
+        Self {
+            speed_min: 0.001,
+            speed_max: 0.005,
+            speed_initial: 0.002,
+            speed_accel: 0.2,
+            rotation_accel: FRAC_PI_2,
+            energy_per_distance: 1.0,
+            energy_per_radian: 0.01,
+        }
+    }
+}
+
+impl Default for EyeConfig {
+    fn default() -> Self {
+        Self {
+            fov_range: 0.25,
+            fov_angle: PI + FRAC_PI_4,
+            cells: 9,
+            noise: NoiseConfig::default(),
+            sensor: SensorConfig::default(),
+            per_category: false,
+            collectors: false,
+            headings: false,
+        }
+    }
+}
+
+impl Default for EpisodesConfig {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            aggregation: AggregationConfig::default(),
+        }
+    }
+}
+
+impl Default for SpatialIndexConfig {
+    fn default() -> Self {
+        Self::Grid { cells: 16 }
+    }
+}
+
+impl Default for FuelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: 5.0,
+            refuel_rate: 0.05,
+        }
+    }
+}
+
+impl Default for WastesConfig {
+    fn default() -> Self {
+        Self {
+            distribution: DistributionConfig::default(),
+            respawn: RespawnConfig::default(),
+            rivers: Vec::new(),
+            max_count: 1000,
+            categories: vec![WasteCategoryConfig::default()],
+        }
+    }
+}
+
+impl SensorConfig {
+    fn collector_radius() -> f32 {
+        0.01
+    }
+}
+
+impl RayChannelConfig {
+    /// One channel per entity class, each with the default normalization
+    pub fn all() -> Vec<Self> {
+        [
+            EntityClass::Waste,
+            EntityClass::Collector,
+            EntityClass::Obstacle,
+            EntityClass::Port,
+        ]
+        .into_iter()
+        .map(|class| Self {
+            class,
+            normalization: NormalizationConfig::default(),
+        })
+        .collect()
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mass: 1.0,
+            thrust: 0.0005,
+            linear_drag: 0.125,
+            angular_drag: 0.5,
+            max_turn_rate: FRAC_PI_8,
+            timestep: 1.0,
+        }
+    }
+}
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        Self {
+            std_dev: 0.0,
+            detection: 1.0,
+            dropout: 0.0,
+            sea_state: 0.0,
+        }
+    }
+}
+
+impl Default for SensesConfig {
+    fn default() -> Self {
+        Self {
+            fuel: true,
+            cargo: true,
+            port: None,
+            speed: false,
+            heading: false,
+            position: false,
+            time: false,
+        }
+    }
+}
+
+impl Default for SeparationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 0.02,
+        }
+    }
+}
+
+impl Default for WasteCategoryConfig {
+    fn default() -> Self {
+        Self {
+            name: "waste".into(),
+            share: 1.0,
+            value: 1.0,
+            radius: None,
+            mass: 1,
+            visibility: 1.0,
+        }
+    }
+}
+
+impl WasteCategoryConfig {
+    pub fn radius(&self, config: &SimulationConfig) -> f32 {
+        self.radius.unwrap_or(config.collection_radius)
+    }
+}
+
+impl Default for CargoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: 5,
+        }
+    }
+}
+
+impl Default for PortsConfig {
+    fn default() -> Self {
+        Self {
+            positions: Vec::new(),
+            radius: 0.03,
+        }
+    }
+}
+
+impl Default for GeneticAlgorithmConfig {
+    fn default() -> Self {
+        Self {
+            selection: SelectionConfig::RouletteWheel,
+            crossover: CrossoverConfig::Uniform,
+            mutation: MutationConfig::Gaussian {
+                chance: 0.01,
+                coeff: 0.3,
+            },
+            elite_count: 0,
+            hall_of_fame_size: 10,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let config = SimulationConfig::default();
+
+        assert_eq!(config.population_size, 40);
+        assert_eq!(config.waste_count, 60);
+        assert_eq!(config.generation_length, 2500);
+        assert_eq!(config.eye.cells, 9);
+    }
+
+    #[test]
+    fn test_partial() {
+        let config: SimulationConfig = serde_json::from_str(
+            r#"{
+                "population_size": 10,
+                "eye": { "cells": 5 },
+                "ga": {
+                    "selection": { "type": "tournament", "size": 3 },
+                    "mutation": {
+                        "type": "scheduled",
+                        "chance": { "constant": 0.05 },
+                        "coeff": {
+                            "exponential": { "initial": 0.5, "decay": 0.99, "min": 0.01 }
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.population_size, 10);
+        assert_eq!(config.waste_count, 60);
+        assert_eq!(config.eye.cells, 5);
+        assert_eq!(config.eye.fov_range, 0.25);
+        assert!(matches!(config.ga.selection, SelectionConfig::Tournament { size: 3 }));
+        assert!(matches!(config.ga.crossover, CrossoverConfig::Uniform));
+        assert!(matches!(config.ga.mutation, MutationConfig::Scheduled { .. }));
+    }
+
+    #[test]
+    fn test_aggregation() {
+        let scores = [4.0, 1.0, 3.0, 2.0];
+
+        assert_eq!(AggregationConfig::Mean.aggregate(&scores), 2.5);
+        assert_eq!(AggregationConfig::Min.aggregate(&scores), 1.0);
+        assert_eq!(AggregationConfig::Quantile { quantile: 0.0 }.aggregate(&scores), 1.0);
+        assert_eq!(AggregationConfig::Quantile { quantile: 0.5 }.aggregate(&scores), 2.5);
+        assert_eq!(AggregationConfig::Quantile { quantile: 1.0 }.aggregate(&scores), 4.0);
+    }
+
+    #[test]
+    fn test_fitness() {
+        let config: SimulationConfig = serde_json::from_str(
+            r#"{
+                "fitness": {
+                    "type": "weighted",
+                    "waste": 1.0,
+                    "distance": 0.0,
+                    "energy": 0.5,
+                    "first_pickup": 0.0,
+                    "collision": 1.0
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            config.fitness,
+            FitnessConfig::Weighted(Weighted { energy, .. }) if energy == 0.5
+        ));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut config = SimulationConfig::default();
+        config.ga.crossover = CrossoverConfig::KPoint { points: 2 };
+
+        let json = serde_json::to_string(&config).unwrap();
+        let config: SimulationConfig = serde_json::from_str(&json).unwrap();
+
+        assert!(matches!(config.ga.crossover, CrossoverConfig::KPoint { points: 2 }));
+    }
+}
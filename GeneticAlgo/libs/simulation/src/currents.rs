@@ -0,0 +1,457 @@
+use crate::*;
+use std::f32::consts::*;
+use std::fmt;
+
+/// Velocity field of the sea, drifting wastes and collectors alike; built
+/// from a `CurrentsConfig`
+#[derive(Clone, Debug)]
+pub enum Currents {
+    Still,
+    Uniform(na::Vector2<f32>),
+    Gyres { count: usize, strength: f32, period: Option<f32> },
+    Eddies { eddies: Vec<Eddy>, radius: f32, strength: f32, drift: f32 },
+    Grid(CurrentsGrid),
+}
+
+/// Center of a vortex, spinning counter-clockwise if `clockwise` is unset
+#[derive(Clone, Debug)]
+pub struct Eddy {
+    /// Where the center starts from
+    center: na::Point2<f32>,
+    clockwise: bool,
+    /// Unit vector the center drifts along
+    heading: na::Vector2<f32>,
+}
+
+/// Velocities sampled at the center of each cell of a `width` x `height`
+/// grid covering the sea, stored row by row
+#[derive(Clone, Debug)]
+pub struct CurrentsGrid {
+    width: usize,
+    height: usize,
+    u: Vec<f32>,
+    v: Vec<f32>,
+}
+
+#[derive(Debug)]
+pub enum CurrentsError {
+    Empty,
+    InvalidLine { line: usize, content: String },
+    MissingCell { x: usize, y: usize },
+}
+
+impl Currents {
+    pub fn from_config(config: &CurrentsConfig) -> Self {
+        match config {
+            CurrentsConfig::None => Self::Still,
+
+            CurrentsConfig::Uniform { u, v } => Self::Uniform(na::Vector2::new(*u, *v)),
+
+            CurrentsConfig::Gyres { count, strength, period } => {
+                assert!(*count > 0);
+
+                Self::Gyres {
+                    count: *count,
+                    strength: *strength,
+                    period: *period,
+                }
+            }
+
+            CurrentsConfig::Eddies { count, radius, strength, seed, drift } => {
+                assert!(*radius > 0.0);
+
+                // Eddies are laid out by their own RNG, so that every sea
+                // (and every episode) of a simulation shares them
+                let mut rng = ChaCha8Rng::seed_from_u64(*seed);
+
+                let mut eddies: Vec<_> = (0..*count)
+                    .map(|_| Eddy {
+                        center: rng.gen(),
+                        clockwise: rng.gen_bool(0.5),
+                        heading: na::Vector2::zeros(),
+                    })
+                    .collect();
+
+                // Picked after the centers, so that they stay where they
+                // were before eddies could drift
+                for eddy in &mut eddies {
+                    let angle = rng.gen_range(0.0..(2.0 * PI));
+                    let (sin, cos) = math::sin_cos(angle);
+                    eddy.heading = na::Vector2::new(cos, sin);
+                }
+
+                Self::Eddies {
+                    eddies,
+                    radius: *radius,
+                    strength: *strength,
+                    drift: *drift,
+                }
+            }
+
+            CurrentsConfig::Grid { width, height, u, v } => {
+                assert!(*width > 0 && *height > 0);
+                assert_eq!(u.len(), width * height);
+                assert_eq!(v.len(), width * height);
+
+                Self::Grid(CurrentsGrid {
+                    width: *width,
+                    height: *height,
+                    u: u.clone(),
+                    v: v.clone(),
+                })
+            }
+        }
+    }
+
+    pub fn is_still(&self) -> bool {
+        matches!(self, Self::Still)
+    }
+
+    /// Drift at `position`, `time` steps into the episode, in distance per
+    /// step
+    pub fn velocity(
+        &self,
+        position: na::Point2<f32>,
+        time: usize,
+        boundary: &BoundaryConfig,
+    ) -> na::Vector2<f32> {
+        match self {
+            Self::Still => na::Vector2::zeros(),
+
+            Self::Uniform(velocity) => *velocity,
+
+            // Cells of the stream function `sin(kx) * sin(ky)`, which
+            // wraps around the torus and neither creates nor swallows water
+            Self::Gyres { count, strength, period } => {
+                let k = 2.0 * PI * (*count as f32);
+                let (sx, cx) = math::sin_cos(k * position.x);
+                let (sy, cy) = math::sin_cos(k * position.y);
+
+                let strength = match period {
+                    Some(period) => strength * math::sin_cos(2.0 * PI * (time as f32) / period).1,
+                    None => *strength,
+                };
+
+                na::Vector2::new(strength * sx * cy, -strength * cx * sy)
+            }
+
+            Self::Eddies { eddies, radius, strength, drift } => eddies
+                .iter()
+                .map(|eddy| {
+                    let center = eddy.center(*drift, time);
+                    eddy.velocity(center, position, *radius, *strength, boundary)
+                })
+                .sum(),
+
+            Self::Grid(grid) => grid.velocity(position),
+        }
+    }
+}
+
+impl Eddy {
+    /// Where the center has drifted to, `time` steps into the episode
+    fn center(&self, drift: f32, time: usize) -> na::Point2<f32> {
+        let center = self.center + self.heading * (drift * time as f32);
+        na::Point2::new(na::wrap(center.x, 0.0, 1.0), na::wrap(center.y, 0.0, 1.0))
+    }
+
+    /// Tangential velocity peaking (at `strength`) at `radius` from
+    /// `center` and fading away past it
+    fn velocity(
+        &self,
+        center: na::Point2<f32>,
+        position: na::Point2<f32>,
+        radius: f32,
+        strength: f32,
+        boundary: &BoundaryConfig,
+    ) -> na::Vector2<f32> {
+        let offset = boundary.offset(center, position);
+
+        let distance = offset.norm() / radius;
+
+        if distance == 0.0 {
+            return na::Vector2::zeros();
+        }
+
+        let speed = strength * distance * math::exp(0.5 * (1.0 - distance * distance));
+
+        let tangent = na::Vector2::new(-offset.y, offset.x) / offset.norm();
+
+        if self.clockwise {
+            -tangent * speed
+        } else {
+            tangent * speed
+        }
+    }
+}
+
+impl CurrentsGrid {
+    /// Bilinear interpolation between the four nearest cell centers,
+    /// wrapping around the torus
+    fn velocity(&self, position: na::Point2<f32>) -> na::Vector2<f32> {
+        let x = na::wrap(position.x, 0.0, 1.0) * (self.width as f32) - 0.5;
+        let y = na::wrap(position.y, 0.0, 1.0) * (self.height as f32) - 0.5;
+
+        let (x0, tx) = (x.floor(), x - x.floor());
+        let (y0, ty) = (y.floor(), y - y.floor());
+
+        let cell = |dx: isize, dy: isize| {
+            let x = (x0 as isize + dx).rem_euclid(self.width as isize) as usize;
+            let y = (y0 as isize + dy).rem_euclid(self.height as isize) as usize;
+            let id = y * self.width + x;
+
+            na::Vector2::new(self.u[id], self.v[id])
+        };
+
+        let top = cell(0, 0) * (1.0 - tx) + cell(1, 0) * tx;
+        let bottom = cell(0, 1) * (1.0 - tx) + cell(1, 1) * tx;
+
+        top * (1.0 - ty) + bottom * ty
+    }
+}
+
+impl CurrentsConfig {
+    /// Parses a gridded field, one cell per line as `x,y,u,v` - where `x`
+    /// and `y` are the cell's column and row - with an optional header;
+    /// the grid's size is given by the largest column and row
+    pub fn from_csv(csv: &str) -> Result<Self, CurrentsError> {
+        let mut cells = Vec::new();
+
+        for (line, content) in csv.lines().enumerate() {
+            let content = content.trim();
+
+            if content.is_empty() {
+                continue;
+            }
+
+            let invalid = || CurrentsError::InvalidLine {
+                line: line + 1,
+                content: content.to_string(),
+            };
+
+            let fields: Vec<_> = content.split(',').map(str::trim).collect();
+
+            if fields.len() != 4 {
+                return Err(invalid());
+            }
+
+            let (Ok(x), Ok(y), Ok(u), Ok(v)) = (
+                fields[0].parse::<usize>(),
+                fields[1].parse::<usize>(),
+                fields[2].parse::<f32>(),
+                fields[3].parse::<f32>(),
+            ) else {
+                if line == 0 {
+                    continue;
+                }
+
+                return Err(invalid());
+            };
+
+            cells.push((x, y, u, v));
+        }
+
+        let width = cells.iter().map(|&(x, ..)| x + 1).max().ok_or(CurrentsError::Empty)?;
+        let height = cells.iter().map(|&(_, y, ..)| y + 1).max().ok_or(CurrentsError::Empty)?;
+
+        let mut u = vec![None; width * height];
+        let mut v = vec![0.0; width * height];
+
+        for (x, y, cell_u, cell_v) in cells {
+            u[y * width + x] = Some(cell_u);
+            v[y * width + x] = cell_v;
+        }
+
+        let u = u
+            .into_iter()
+            .enumerate()
+            .map(|(id, u)| {
+                u.ok_or(CurrentsError::MissingCell {
+                    x: id % width,
+                    y: id / width,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::Grid { width, height, u, v })
+    }
+}
+
+impl fmt::Display for CurrentsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "currents grid contains no cells"),
+            Self::InvalidLine { line, content } => write!(
+                f,
+                "line {}: expected `x,y,u,v`, got `{}`",
+                line, content,
+            ),
+            Self::MissingCell { x, y } => {
+                write!(f, "currents grid is missing cell ({}, {})", x, y)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CurrentsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    const TORUS: BoundaryConfig = BoundaryConfig::Toroidal;
+
+    #[test]
+    fn test_uniform() {
+        let currents = Currents::from_config(&CurrentsConfig::Uniform { u: 0.1, v: -0.2 });
+
+        assert_eq!(
+            currents.velocity(na::Point2::new(0.3, 0.9), 0, &TORUS),
+            na::Vector2::new(0.1, -0.2),
+        );
+    }
+
+    #[test]
+    fn test_gyres() {
+        let currents = Currents::from_config(&CurrentsConfig::Gyres {
+            count: 1,
+            strength: 0.01,
+            period: None,
+        });
+
+        let velocity = currents.velocity(na::Point2::new(0.25, 0.0), 0, &TORUS);
+        assert_relative_eq!(velocity.x, 0.01);
+        assert_relative_eq!(velocity.y, 0.0, epsilon = 1e-6);
+
+        // The center of a cell is still
+        let velocity = currents.velocity(na::Point2::new(0.25, 0.25), 0, &TORUS);
+        assert_relative_eq!(velocity.norm(), 0.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_gyres_period() {
+        let currents = Currents::from_config(&CurrentsConfig::Gyres {
+            count: 1,
+            strength: 0.01,
+            period: Some(100.0),
+        });
+
+        let position = na::Point2::new(0.25, 0.0);
+
+        assert_relative_eq!(currents.velocity(position, 0, &TORUS).x, 0.01);
+        assert_relative_eq!(currents.velocity(position, 25, &TORUS).x, 0.0, epsilon = 1e-6);
+        assert_relative_eq!(currents.velocity(position, 50, &TORUS).x, -0.01, epsilon = 1e-6);
+        assert_relative_eq!(currents.velocity(position, 100, &TORUS).x, 0.01, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_eddies() {
+        let config = CurrentsConfig::Eddies {
+            count: 3,
+            radius: 0.1,
+            strength: 0.01,
+            seed: 7,
+            drift: 0.0,
+        };
+
+        let position = na::Point2::new(0.4, 0.6);
+
+        assert_eq!(
+            Currents::from_config(&config).velocity(position, 0, &TORUS),
+            Currents::from_config(&config).velocity(position, 0, &TORUS),
+        );
+
+        let Currents::Eddies { eddies, .. } = Currents::from_config(&config) else {
+            unreachable!();
+        };
+
+        // Peak speed at `radius` from a lone eddy's center
+        let eddy = &eddies[0];
+        let position = eddy.center + na::Vector2::new(0.1, 0.0);
+        let velocity = eddy.velocity(eddy.center, position, 0.1, 0.01, &TORUS);
+
+        assert_relative_eq!(velocity.norm(), 0.01, epsilon = 1e-6);
+        assert_relative_eq!(velocity.x, 0.0, epsilon = 1e-6);
+
+        // Only a torus carries an eddy across the edges
+        let center = na::Point2::new(0.02, 0.5);
+        let position = na::Point2::new(0.92, 0.5);
+
+        let velocity = eddy.velocity(center, position, 0.1, 0.01, &TORUS);
+        assert_relative_eq!(velocity.norm(), 0.01, epsilon = 1e-6);
+
+        let velocity = eddy.velocity(center, position, 0.1, 0.01, &BoundaryConfig::Reflective);
+        assert_relative_eq!(velocity.norm(), 0.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_eddies_drift() {
+        let config = |drift| CurrentsConfig::Eddies {
+            count: 1,
+            radius: 0.1,
+            strength: 0.01,
+            seed: 7,
+            drift,
+        };
+
+        let still = Currents::from_config(&config(0.0));
+        let drifting = Currents::from_config(&config(0.001));
+
+        let Currents::Eddies { eddies, .. } = &drifting else {
+            unreachable!();
+        };
+
+        let position = eddies[0].center + na::Vector2::new(0.1, 0.0);
+
+        // Drifting does not move the eddy around, only along
+        let at = |currents: &Currents, time| currents.velocity(position, time, &TORUS);
+
+        assert_eq!(at(&still, 0), at(&drifting, 0));
+        assert_eq!(at(&still, 10), at(&still, 0));
+        assert_ne!(at(&drifting, 10), at(&drifting, 0));
+
+        let center = eddies[0].center(0.001, 10);
+        assert_relative_eq!(
+            na::distance(&center, &eddies[0].center),
+            0.01,
+            epsilon = 1e-5,
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let config = CurrentsConfig::from_csv(
+            "x,y,u,v\n0,0,1.0,0.0\n1,0,0.0,0.0\n0,1,1.0,0.0\n1,1,0.0,0.0\n",
+        )
+        .unwrap();
+
+        let currents = Currents::from_config(&config);
+
+        // Cell centers, and halfway between them
+        assert_relative_eq!(currents.velocity(na::Point2::new(0.25, 0.25), 0, &TORUS).x, 1.0);
+        assert_relative_eq!(currents.velocity(na::Point2::new(0.75, 0.25), 0, &TORUS).x, 0.0);
+        assert_relative_eq!(currents.velocity(na::Point2::new(0.5, 0.25), 0, &TORUS).x, 0.5);
+
+        // Across the edge
+        assert_relative_eq!(currents.velocity(na::Point2::new(0.0, 0.25), 0, &TORUS).x, 0.5);
+    }
+
+    #[test]
+    fn test_csv_errors() {
+        assert!(matches!(
+            CurrentsConfig::from_csv("x,y,u,v\n"),
+            Err(CurrentsError::Empty),
+        ));
+
+        assert!(matches!(
+            CurrentsConfig::from_csv("0,0,1.0,0.0\n1,1,1.0\n"),
+            Err(CurrentsError::InvalidLine { line: 2, .. }),
+        ));
+
+        assert!(matches!(
+            CurrentsConfig::from_csv("0,0,1.0,0.0\n1,1,1.0,0.0\n"),
+            Err(CurrentsError::MissingCell { x: 1, y: 0 }),
+        ));
+    }
+}
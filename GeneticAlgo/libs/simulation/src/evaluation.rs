@@ -0,0 +1,120 @@
+use crate::*;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Plays the episodes the population is judged on and records each
+/// collector's per-episode scores (as given by `fitness`), in
+/// `Collector::scores`.
+///
+/// With shared evaluation, the visible sea has just played the first
+/// episode; the remaining ones are played by the whole population in fresh
+/// seas.
+pub(crate) fn evaluate(
+    mut collectors: Vec<Collector>,
+    config: &SimulationConfig,
+    fitness: &dyn FitnessFunction,
+    rng: &mut dyn RngCore,
+) -> Vec<Collector> {
+    let episodes = config.episodes.count;
+    assert!(episodes > 0);
+
+    match config.evaluation {
+        EvaluationConfig::Shared => {
+            end_episode(&mut collectors, config, fitness);
+
+            for _ in 1..episodes {
+                collectors = run_episode(collectors, rng.gen(), config);
+                end_episode(&mut collectors, config, fitness);
+            }
+        }
+
+        EvaluationConfig::Isolated { batch_size } => {
+            for _ in 0..episodes {
+                collectors = evaluate_isolated(collectors, batch_size, config, rng);
+                end_episode(&mut collectors, config, fitness);
+            }
+        }
+    }
+
+    collectors
+}
+
+/// Scores the episode, minus the boundary's penalty (if any)
+fn end_episode(
+    collectors: &mut [Collector],
+    config: &SimulationConfig,
+    fitness: &dyn FitnessFunction,
+) {
+    for collector in collectors {
+        let mut score = fitness.score(&collector.metrics);
+
+        if let BoundaryConfig::Wall { .. } = config.boundary {
+            score = (score - config.boundary.penalty(&collector.metrics)).max(0.0);
+        }
+
+        collector.scores.push(score);
+    }
+}
+
+/// Runs every batch of `batch_size` collectors through its own full-length
+/// episode, in its own sea, and hands the collectors back (in their original
+/// order) with their metrics filled in.
+///
+/// Each batch gets its own RNG, seeded upfront from `rng`, so the outcome is
+/// the same whether the batches run in parallel (with the `parallel`
+/// feature) or one after another.
+fn evaluate_isolated(
+    collectors: Vec<Collector>,
+    batch_size: usize,
+    config: &SimulationConfig,
+    rng: &mut dyn RngCore,
+) -> Vec<Collector> {
+    assert!(batch_size > 0);
+
+    let mut collectors = collectors.into_iter();
+    let mut batches = Vec::new();
+
+    loop {
+        let batch: Vec<_> = collectors.by_ref().take(batch_size).collect();
+
+        if batch.is_empty() {
+            break;
+        }
+
+        batches.push((rng.gen::<u64>(), batch));
+    }
+
+    #[cfg(feature = "parallel")]
+    let batches = batches.into_par_iter();
+
+    #[cfg(not(feature = "parallel"))]
+    let batches = batches.into_iter();
+
+    let batches: Vec<_> = batches
+        .map(|(seed, batch)| run_episode(batch, seed, config))
+        .collect();
+
+    batches.into_iter().flatten().collect()
+}
+
+fn run_episode(
+    mut collectors: Vec<Collector>,
+    seed: u64,
+    config: &SimulationConfig,
+) -> Vec<Collector> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    for collector in &mut collectors {
+        collector.respawn(config, &mut rng);
+    }
+
+    let mut sea = Sea::with_collectors(collectors, config, &mut rng);
+
+    // Same number of steps as a generation of the shared sea
+    for _ in 0..=config.generation_length {
+        sea.step(config, &mut rng);
+    }
+
+    sea.collectors
+}
@@ -1,81 +1,81 @@
 use crate::*;
-use std::f32::consts::*;
+use std::fmt;
 
-//Range of the Field of view (1 -> full map width, 0 -> nothing)
-const FOV_RANGE: f32 = 0.25;
+/// Turns what surrounds a collector into the values its brain is fed
+pub trait Eye: fmt::Debug + Send + Sync {
+    /// Number of values returned by `process_vision()`
+    fn outputs(&self) -> usize;
 
-//Angle of the Field of view in radians
-const FOV_ANGLE: f32 = PI + FRAC_PI_4;
-
-//Number of directions the eye is looking to
-const CELLS: usize = 9;
-
-#[derive(Debug)]
-pub struct Eye {
-    fov_range: f32,
-    fov_angle: f32,
-    cells: usize, //to divide the fov_angle in equal parts
+    fn process_vision(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        surroundings: &Surroundings,
+    ) -> Vec<f32>;
 }
 
-impl Eye {
-    fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
-        assert!(fov_range > 0.0);
-        assert!(fov_angle > 0.0);
-        assert!(cells > 0);
+/// Everything an eye may look at
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Surroundings<'a> {
+    pub wastes: &'a [Waste],
 
-        Self { fov_range, fov_angle, cells }
-    }
+    /// Narrows down the wastes worth looking at, if given
+    pub waste_index: Option<&'a SpatialIndex>,
 
-    pub fn cells(&self) -> usize {
-        self.cells
-    }
+    pub obstacles: &'a [Obstacle],
+    pub ports: &'a [Port],
+    pub collectors: &'a [Collector],
 
-    pub fn process_vision(
+    /// Position of the looking collector within `collectors`, which does
+    /// not see itself
+    pub viewer: Option<usize>,
+}
+
+impl<'a> Surroundings<'a> {
+    /// Wastes possibly within `range` of `position`
+    pub fn wastes_near(
         &self,
         position: na::Point2<f32>,
-        rotation: na::Rotation2<f32>,
-        wastes: &[Waste],
-    ) -> Vec<f32> {
-        let mut cells = vec![0.0; self.cells];
-        for waste in wastes {
-            let vec = waste.position - position;
+        range: f32,
+    ) -> Box<dyn Iterator<Item = &'a Waste> + 'a> {
+        let wastes = self.wastes;
 
-            //Check if waste is in range
-            let dist = vec.norm();
-            if dist >= self.fov_range {
-                continue;
-            }
-
-            //Check if waste is in the vision span
-            let angle = na::Rotation2::rotation_between(
-                &na::Vector2::y(),
-                &vec,
-            ).angle();
-            let angle = angle - rotation.angle();
-            let angle = na::wrap(angle, -PI, PI);
-            if angle < -self.fov_angle / 2.0 ||
-               angle > self.fov_angle / 2.0
-            {
-                continue;
-            }
-
-            //Find corresponding cell index
-            let angle = angle + self.fov_angle / 2.0;
-            let cell = angle / self.fov_angle;
-            let cell = cell * (self.cells as f32);
-            let cell = (cell as usize).min(cells.len() - 1);
-
-            //Compute energy required to collect this waste
-            let energy = (self.fov_range - dist) / self.fov_range;
-            cells[cell] += energy;
+        match self.waste_index {
+            Some(index) => Box::new(
+                index
+                    .query(position, range)
+                    .into_iter()
+                    .map(move |id| &wastes[id]),
+            ),
+            None => Box::new(wastes.iter()),
         }
+    }
+
+    /// Every collector but the looking one
+    pub fn others(&self) -> impl Iterator<Item = &'a Collector> {
+        let viewer = self.viewer;
 
-        cells
+        self.collectors
+            .iter()
+            .enumerate()
+            .filter(move |&(id, _)| Some(id) != viewer)
+            .map(|(_, collector)| collector)
     }
 }
 
-impl Default for Eye {
-    fn default() -> Self {
-        Self::new(FOV_RANGE, FOV_ANGLE, CELLS)
+impl SimulationConfig {
+    /// Builds the eye described by `eye.sensor`
+    pub fn build_eye(&self) -> Box<dyn Eye> {
+        match &self.eye.sensor {
+            SensorConfig::Cells => Box::new(CellEye::from_config(
+                &self.eye,
+                &self.wastes.categories,
+                self.boundary,
+            )),
+
+            SensorConfig::Rays { channels, collector_radius } => {
+                Box::new(RayEye::from_config(self, channels, *collector_radius))
+            }
+        }
     }
 }
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+/// What a collector did during a single episode
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EpisodeMetrics {
+    /// Number of steps played so far
+    pub steps: usize,
+
+    /// Wastes picked up, delivered or not
+    pub wastes_collected: usize,
+
+    /// Wastes unloaded at a port, only tracked with a cargo model
+    pub wastes_delivered: usize,
+
+    /// Total value (see `WasteCategoryConfig::value`) of the wastes
+    /// picked up and unloaded
+    pub value_collected: f32,
+    pub value_delivered: f32,
+
+    pub distance: f32,
+
+    /// See `CollectorConfig::energy_per_distance` and `energy_per_radian`
+    pub energy: f32,
+
+    /// Step of the first pickup, `None` if nothing was collected
+    pub first_pickup: Option<usize>,
+
+    /// Collisions with obstacles and other collectors
+    pub collisions: usize,
+
+    /// Times the edge of a walled sea was hit, see `BoundaryConfig::Wall`
+    pub wall_hits: usize,
+
+    /// Fuel left, `None` without a fuel model
+    pub fuel: Option<f32>,
+
+    /// Wastes on board, `None` without a cargo model
+    pub cargo: Option<usize>,
+}
+
+impl EpisodeMetrics {
+    /// Wastes counting toward fitness: with a cargo model only delivered
+    /// ones do, otherwise every waste collected does
+    pub fn wastes(&self) -> usize {
+        if self.cargo.is_some() {
+            self.wastes_delivered
+        } else {
+            self.wastes_collected
+        }
+    }
+
+    /// Same as `wastes()`, each waste counting as much as it is worth
+    pub fn value(&self) -> f32 {
+        if self.cargo.is_some() {
+            self.value_delivered
+        } else {
+            self.value_collected
+        }
+    }
+}
+
+/// Turns what a collector did during an episode into a score; scores of
+/// several episodes are then aggregated into the collector's fitness (see
+/// `AggregationConfig`).
+///
+/// Selection methods expect non-negative fitness.
+pub trait FitnessFunction {
+    fn score(&self, metrics: &EpisodeMetrics) -> f32;
+}
+
+/// The original objective, one point per waste (or rather, per unit of
+/// value)
+#[derive(Clone, Debug)]
+pub struct WastesCollected;
+
+/// Value collected per unit of energy spent
+#[derive(Clone, Debug)]
+pub struct WastesPerEnergy;
+
+/// Value collected, plus up to one point for an early first pickup
+#[derive(Clone, Debug)]
+pub struct EarlyPickup;
+
+/// Linear combination of the metrics, clamped at zero; costs (distance,
+/// energy, time to first pickup, collisions) are subtracted
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Weighted {
+    pub waste: f32,
+    pub distance: f32,
+    pub energy: f32,
+    pub first_pickup: f32,
+    pub collision: f32,
+}
+
+impl FitnessFunction for WastesCollected {
+    fn score(&self, metrics: &EpisodeMetrics) -> f32 {
+        metrics.value()
+    }
+}
+
+impl FitnessFunction for WastesPerEnergy {
+    fn score(&self, metrics: &EpisodeMetrics) -> f32 {
+        metrics.value() / (1.0 + metrics.energy)
+    }
+}
+
+impl FitnessFunction for EarlyPickup {
+    fn score(&self, metrics: &EpisodeMetrics) -> f32 {
+        metrics.value() + time_left(metrics)
+    }
+}
+
+impl FitnessFunction for Weighted {
+    fn score(&self, metrics: &EpisodeMetrics) -> f32 {
+        let score = self.waste * metrics.value()
+            - self.distance * metrics.distance
+            - self.energy * metrics.energy
+            - self.first_pickup * (1.0 - time_left(metrics))
+            - self.collision * (metrics.collisions as f32);
+
+        score.max(0.0)
+    }
+}
+
+impl<F> FitnessFunction for Box<F>
+where
+    F: FitnessFunction + ?Sized,
+{
+    fn score(&self, metrics: &EpisodeMetrics) -> f32 {
+        (**self).score(metrics)
+    }
+}
+
+/// Share of the episode left after the first pickup (0 without any)
+fn time_left(metrics: &EpisodeMetrics) -> f32 {
+    match metrics.first_pickup {
+        Some(step) if metrics.steps > 0 => {
+            1.0 - (step as f32) / (metrics.steps as f32)
+        }
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> EpisodeMetrics {
+        EpisodeMetrics {
+            steps: 100,
+            wastes_collected: 3,
+            wastes_delivered: 0,
+            value_collected: 3.0,
+            value_delivered: 0.0,
+            distance: 0.5,
+            energy: 1.0,
+            first_pickup: Some(25),
+            collisions: 2,
+            wall_hits: 0,
+            fuel: None,
+            cargo: None,
+        }
+    }
+
+    #[test]
+    fn test_builtins() {
+        assert_eq!(WastesCollected.score(&metrics()), 3.0);
+        assert_eq!(WastesPerEnergy.score(&metrics()), 1.5);
+        assert_eq!(EarlyPickup.score(&metrics()), 3.75);
+    }
+
+    #[test]
+    fn test_delivered() {
+        let metrics = EpisodeMetrics {
+            wastes_delivered: 1,
+            value_delivered: 1.0,
+            cargo: Some(2),
+            ..metrics()
+        };
+
+        assert_eq!(WastesCollected.score(&metrics), 1.0);
+    }
+
+    #[test]
+    fn test_weighted() {
+        let fitness = Weighted {
+            waste: 2.0,
+            distance: 1.0,
+            energy: 0.5,
+            first_pickup: 1.0,
+            collision: 0.25,
+        };
+
+        assert_eq!(fitness.score(&metrics()), 6.0 - 0.5 - 0.5 - 0.25 - 0.5);
+
+        let idle = EpisodeMetrics {
+            collisions: 100,
+            ..metrics()
+        };
+
+        assert_eq!(fitness.score(&idle), 0.0);
+    }
+
+    #[test]
+    fn test_no_pickup() {
+        let metrics = EpisodeMetrics {
+            wastes_collected: 0,
+            value_collected: 0.0,
+            first_pickup: None,
+            ..metrics()
+        };
+
+        assert_eq!(EarlyPickup.score(&metrics), 0.0);
+    }
+}
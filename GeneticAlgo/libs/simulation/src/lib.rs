@@ -1,139 +1,373 @@
-pub use self::{collector::*, eye::*,brain::*, waste::*, sea::*};
+pub use self::{boundary::*, cell_eye::*, collector::*, config::*, currents::*, eye::*,brain::*, fitness::*, obstacle::*, port::*, ray_eye::*, waste::*, sea::*, snapshot::*, spatial_index::*, spawner::*, validation::*};
+pub use lib_genetic_algorithm::{HallOfFame, HallOfFameEntry, Schedule, Statistics};
 
+mod boundary;
 mod collector;
+mod config;
+mod currents;
 mod collector_individual;
+mod evaluation;
 mod eye;
+mod cell_eye;
+mod ray_eye;
 mod brain;
+mod fitness;
+mod math;
+mod noise;
 mod waste;
+mod obstacle;
+mod physics;
+mod port;
 mod sea;
+mod senses;
+mod snapshot;
+mod spatial_index;
+mod spawner;
+mod validation;
 
 use self::collector_individual::*;
 use lib_genetic_algorithm as ga;
 use lib_neural_network as nn;
 use nalgebra as na;
-use rand::{Rng, RngCore};
-
-use std::f32::consts::FRAC_PI_2;
-const SPEED_MIN: f32 = 0.001;
-const SPEED_MAX: f32 = 0.005;
-const SPEED_ACCEL: f32 = 0.2;
-const ROTATION_ACCEL: f32 = FRAC_PI_2;
-
-/// Number of steps for each generation
-const GENERATION_LENGTH: usize = 2500; 
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::mem;
 
 pub struct Simulation{
+    pub(crate) config: SimulationConfig,
     pub(crate) sea: Sea,
-    pub(crate) ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
+    pub(crate) ga: ga::GeneticAlgorithm<SelectionConfig>,
     pub(crate) age: usize,
+    pub(crate) hall_of_fame: ga::HallOfFame,
+    pub(crate) episode_scores: Vec<Vec<f32>>,
+    pub(crate) fitness: Box<dyn FitnessFunction>,
+    /// Sea shown instead of the training one while a brain is replayed
+    pub(crate) replay: Option<Sea>,
+    /// Drives the visible sea with isolated evaluation, so that previewing
+    /// a generation leaves the training RNG alone
+    pub(crate) preview_rng: ChaCha8Rng,
 }
 
 impl Simulation {
     pub fn random(rng: &mut dyn RngCore) -> Self {
-        let sea = Sea::random(rng);
+        Self::new(SimulationConfig::default(), rng).expect("default config is valid")
+    }
 
-        let ga = ga::GeneticAlgorithm::new(
-            ga::RouletteWheelSelection::default(),
-            ga::UniformCrossover::default(),
-            ga::GaussianMutation::new(0.01, 0.3),
-        );
-        Self {
-            sea: Sea::random(rng),
-            ga: ga,
+    /// Creates a simulation together with the RNG that has to drive it.
+    ///
+    /// Feeding the returned RNG to `step()` / `train()` yields bit-identical
+    /// `Statistics` for a given seed and config, on native and WASM targets
+    /// alike: ChaCha8 produces the same stream on both, and `sin`, `atan2`,
+    /// `exp` and the like are computed by `libm` rather than the platform.
+    pub fn from_seed(
+        config: SimulationConfig,
+        seed: u64,
+    ) -> Result<(Self, ChaCha8Rng), ConfigError> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let sim = Self::new(config, &mut rng)?;
+
+        Ok((sim, rng))
+    }
+
+    pub fn new(config: SimulationConfig, rng: &mut dyn RngCore) -> Result<Self, ConfigError> {
+        config.validate()?;
+
+        let sea = Sea::random(&config, rng);
+        let ga = config.genetic_algorithm();
+        let hall_of_fame = ga::HallOfFame::new(config.ga.hall_of_fame_size);
+        let fitness = Box::new(config.fitness.clone());
+
+        Ok(Self {
+            config,
+            sea,
+            ga,
             age: 0,
+            hall_of_fame,
+            episode_scores: Vec::new(),
+            fitness,
+            replay: None,
+            preview_rng: ChaCha8Rng::seed_from_u64(0),
+        })
+    }
+
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    /// Replaces the sea's currents, e.g. with a field loaded from CSV
+    pub fn set_currents(&mut self, currents: CurrentsConfig) {
+        self.sea.currents = Currents::from_config(&currents);
+
+        if let Some(replay) = &mut self.replay {
+            replay.currents = Currents::from_config(&currents);
+        }
+
+        self.config.currents = currents;
+    }
+
+    /// Replaces the sea's obstacles, e.g. with polygons loaded from a file
+    pub fn set_obstacles(&mut self, obstacles: ObstaclesConfig) {
+        self.sea.obstacles = obstacles.polygons.iter().map(Obstacle::from_config).collect();
+
+        if let Some(replay) = &mut self.replay {
+            replay.obstacles = self.sea.obstacles.clone();
         }
+
+        self.config.obstacles = obstacles;
+    }
+
+    /// Switches to one of the built-in fitness functions, from the next
+    /// evaluation on
+    pub fn set_fitness(&mut self, fitness: FitnessConfig) {
+        self.fitness = Box::new(fitness.clone());
+        self.config.fitness = fitness;
+    }
+
+    /// Switches to a custom fitness function, from the next evaluation on;
+    /// it is not part of the config, and so is not saved by `snapshot()`
+    pub fn with_fitness_function(
+        mut self,
+        fitness: impl FitnessFunction + 'static,
+    ) -> Self {
+        self.fitness = Box::new(fitness);
+        self
     }
 
+    /// Sea being shown: the replay's while one runs, the training one
+    /// otherwise
     pub fn sea(&self) -> &Sea{
-        &self.sea
+        self.replay.as_ref().unwrap_or(&self.sea)
     }
 
-    pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<ga::Statistics> {
-        self.process_collisions(rng);
-        self.process_brains();
-        self.process_movements();
+    /// Number of generations evolved so far
+    pub fn generation(&self) -> usize {
+        self.ga.generation()
+    }
 
-        self.age += 1;
+    pub fn hall_of_fame(&self) -> &ga::HallOfFame {
+        &self.hall_of_fame
+    }
 
-        if self.age > GENERATION_LENGTH {
-            Some(self.evolve(rng))
-        } else {
-            None
-        }
+    /// Per-episode scores of each collector of the last evaluated
+    /// generation, in the order of the `Statistics` it produced
+    pub fn episode_scores(&self) -> &[Vec<f32>] {
+        &self.episode_scores
     }
 
-    pub fn train(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
-        loop {
-            if let Some(summary) = self.step(rng) {
-                return summary;
-            }
+    /// Plays a generation-long episode, in a sea of its own, with every
+    /// collector driven by the all-time champion's brain; returns `false` if
+    /// no generation has been evaluated yet.
+    ///
+    /// Training is paused meanwhile: `step()` advances the replay until it
+    /// ends, and the training population is left untouched.
+    pub fn replay_champion(&mut self, rng: &mut dyn RngCore) -> bool {
+        let Some(champion) = self.hall_of_fame.champion() else {
+            return false;
+        };
+
+        let chromosome = champion.chromosome().clone();
+        self.replay(chromosome, rng);
+        true
+    }
+
+    /// Same as `replay_champion()`, but for a previously saved brain
+    pub fn replay_brain(
+        &mut self,
+        brain: &BrainSnapshot,
+        rng: &mut dyn RngCore,
+    ) -> Result<(), SnapshotError> {
+        let chromosome = brain.to_chromosome(&self.config)?;
+        self.replay(chromosome, rng);
+        Ok(())
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Ends the running replay (if any), going back to the training sea
+    pub fn stop_replay(&mut self) {
+        self.replay = None;
+    }
+
+    fn replay(&mut self, chromosome: ga::Chromosome, rng: &mut dyn RngCore) {
+        let collectors = (0..self.sea.collectors.len())
+            .map(|_| {
+                Collector::from_chromosome(chromosome.clone(), &self.config, rng)
+            })
+            .collect();
+
+        self.replay = Some(Sea::with_collectors(collectors, &self.config, rng));
+    }
+
+    /// Saves the current population (not evaluated yet, hence without
+    /// fitness) and the hall of fame
+    pub fn snapshot(&self) -> PopulationSnapshot {
+        let config_hash = self.config.hash();
+
+        let brains = self
+            .sea
+            .collectors
+            .iter()
+            .map(|collector| {
+                BrainSnapshot::new(
+                    &collector.brain,
+                    self.generation(),
+                    None,
+                    config_hash,
+                )
+            })
+            .collect();
+
+        let hall_of_fame = self
+            .hall_of_fame
+            .entries()
+            .iter()
+            .map(|entry| {
+                BrainSnapshot::from_hall_of_fame(entry, &self.config, config_hash)
+            })
+            .collect();
+
+        PopulationSnapshot {
+            config: self.config.clone(),
+            config_hash,
+            generation: self.generation(),
+            brains,
+            hall_of_fame,
         }
     }
 
-    fn process_movements(&mut self) {
-        for collector in &mut self.sea.collectors {
-            collector.position += 
-                collector.rotation * na::Vector2::new(0.0, collector.speed);
-            collector.position.x = na::wrap(collector.position.x, 0.0, 1.0);
-            collector.position.y = na::wrap(collector.position.y, 0.0, 1.0);
+    /// The all-time champion, ready to be exported
+    pub fn champion_snapshot(&self) -> Option<BrainSnapshot> {
+        self.hall_of_fame.champion().map(|entry| {
+            BrainSnapshot::from_hall_of_fame(entry, &self.config, self.config.hash())
+        })
+    }
+
+    /// Resumes training from a saved population, starting a fresh generation
+    pub fn from_snapshot(
+        snapshot: PopulationSnapshot,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self, SnapshotError> {
+        if snapshot.brains.is_empty() {
+            return Err(SnapshotError::EmptyPopulation);
         }
+
+        let mut config = snapshot.config;
+        config.population_size = snapshot.brains.len();
+        config.validate().map_err(SnapshotError::Config)?;
+
+        let chromosomes = snapshot
+            .brains
+            .iter()
+            .map(|brain| brain.to_chromosome(&config))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut hall_of_fame = ga::HallOfFame::new(config.ga.hall_of_fame_size);
+
+        for brain in &snapshot.hall_of_fame {
+            hall_of_fame.insert(ga::HallOfFameEntry::new(
+                brain.to_chromosome(&config)?,
+                brain.fitness.unwrap_or(0.0),
+                brain.generation,
+            ));
+        }
+
+        let mut sim = Self::new(config, rng).map_err(SnapshotError::Config)?;
+
+        sim.sea.collectors = chromosomes
+            .into_iter()
+            .map(|chromosome| Collector::from_chromosome(chromosome, &sim.config, rng))
+            .collect();
+
+        sim.ga = sim.config.genetic_algorithm().with_generation(snapshot.generation);
+        sim.hall_of_fame = hall_of_fame;
+
+        Ok(sim)
     }
 
-    fn process_collisions(&mut self, rng: &mut dyn RngCore) {
-        for collector in &mut self.sea.collectors {
-            for waste in &mut self.sea.wastes {
-                let distance = na::distance(
-                    &collector.position,
-                    &waste.position,
-                );
+    /// Advances the visible sea by one step, evolving the population at the
+    /// end of the generation.
+    ///
+    /// With isolated evaluation, the visible sea is only a preview: it is
+    /// driven by an RNG of its own and its outcome is not scored, as every
+    /// collector is evaluated in its own seas once the generation ends.
+    /// Stepping through a generation thus yields the same `Statistics` as
+    /// `train()`.
+    pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<ga::Statistics> {
+        if let Some(replay) = &mut self.replay {
+            replay.step(&self.config, rng);
 
-                if distance <= 0.01 {
-                    collector.proficiency += 1;
-                    waste.position = rng.gen();
-                }
+            if replay.age > self.config.generation_length {
+                self.replay = None;
             }
+
+            return None;
         }
-    }
 
-    fn process_brains(&mut self) {
-        for collector in &mut self.sea.collectors {
-            let vision = collector.eye.process_vision(
-                collector.position,
-                collector.rotation,
-                &self.sea.wastes,
-            );
+        if let EvaluationConfig::Isolated { .. } = self.config.evaluation {
+            if self.age == 0 {
+                let seed = self.ga.generation() as u64;
+                self.preview_rng = ChaCha8Rng::seed_from_u64(seed);
+            }
 
-            let response = collector.brain.nn.propagate(vision);
+            self.sea.step(&self.config, &mut self.preview_rng);
+        } else {
+            self.sea.step(&self.config, rng);
+        }
 
-            let speed = response[0].clamp(
-                -SPEED_ACCEL,
-                SPEED_ACCEL,
-            );
+        self.age += 1;
 
-            let rotation = response[1].clamp(
-                -ROTATION_ACCEL,
-                ROTATION_ACCEL,
-            );
+        if self.age > self.config.generation_length {
+            Some(self.evolve(rng))
+        } else {
+            None
+        }
+    }
 
-            collector.speed =
-                (collector.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
+    /// Runs until the end of the current generation and evolves it, ending
+    /// any running replay first; with isolated evaluation, the visible sea is
+    /// not stepped at all
+    pub fn train(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
+        self.stop_replay();
 
-            collector.rotation = na::Rotation2::new(
-                collector.rotation.angle() + rotation,
-            );
+        if let EvaluationConfig::Isolated { .. } = self.config.evaluation {
+            return self.evolve(rng);
+        }
+
+        loop {
+            if let Some(summary) = self.step(rng) {
+                return summary;
+            }
         }
     }
 
-    fn evolve(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {    self.age = 0;
+    fn evolve(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
+        self.age = 0;
+
+        let collectors = mem::take(&mut self.sea.collectors);
+        self.sea.collectors =
+            evaluation::evaluate(collectors, &self.config, &*self.fitness, rng);
+
+        self.episode_scores = self
+            .sea
+            .collectors
+            .iter()
+            .map(|collector| collector.scores.clone())
+            .collect();
+
         // Transforms `Vec<Collector>` to `Vec<CollectorIndividual>`
         // in order perform evolution with the genetic algorithm 
         let current_population: Vec<_> = self
             .sea
             .collectors
             .iter()
-            .map(CollectorIndividual::from_collector)
+            .map(|collector| CollectorIndividual::from_collector(collector, &self.config))
             .collect();
 
+        self.hall_of_fame
+            .update(&current_population, self.ga.generation());
+
         // Evolve
         let (evolved_population, stats) = self.ga.evolve(
             rng,
@@ -143,14 +377,892 @@ impl Simulation {
         // Transforms `Vec<CollectorIndividual>` back into `Vec<Collector>`
         self.sea.collectors = evolved_population
             .into_iter()
-            .map(|individual| individual.into_collector(rng))
+            .map(|individual| individual.into_collector(&self.config, rng))
             .collect();
 
         // Restart wastes
-        for waste in &mut self.sea.wastes {
-            waste.position = rng.gen();
-        }
+        self.sea.reset_wastes(&self.config, rng);
 
         stats
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SimulationConfig {
+        SimulationConfig {
+            population_size: 6,
+            waste_count: 20,
+            generation_length: 200,
+            ..Default::default()
+        }
+    }
+
+    fn run(seed: u64, generations: usize) -> Vec<Statistics> {
+        let (mut sim, mut rng) = Simulation::from_seed(config(), seed).unwrap();
+
+        (0..generations)
+            .map(|_| sim.train(&mut rng))
+            .collect()
+    }
+
+    mod from_seed {
+        use super::*;
+
+        #[test]
+        fn test_reproducible() {
+            let a = run(42, 3);
+            let b = run(42, 3);
+
+            assert_eq!(a, b);
+        }
+
+        /// Pins the outcome of a seeded run, which has to be the same on
+        /// native and WASM targets (`wasm-pack test --node libs/simulation`
+        /// runs it on the latter); update the values whenever a change to RNG
+        /// consumption or float arithmetic is intended
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        fn test_golden() {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 42).unwrap();
+
+            let stats: Vec<_> = (0..3)
+                .map(|_| {
+                    let stats = sim.train(&mut rng);
+
+                    (
+                        stats.min_fitness(),
+                        stats.max_fitness(),
+                        stats.avg_fitness(),
+                        stats.best_index(),
+                        stats.diversity(),
+                    )
+                })
+                .collect();
+
+            for _ in 0..50 {
+                sim.step(&mut rng);
+            }
+
+            let position = sim.sea().collectors()[0].position();
+
+            assert_eq!(
+                stats,
+                [
+                    (0.0, 2.0, 0.6666667, 0, 11.604578),
+                    (0.0, 3.0, 0.8333333, 2, 8.040843),
+                    (0.0, 4.0, 1.5, 4, 5.6150036),
+                ],
+            );
+
+            assert_eq!(position, na::Point2::new(0.9268707, 0.43068245));
+        }
+
+        #[test]
+        fn test_different_seeds() {
+            let (sim_a, _) = Simulation::from_seed(config(), 1).unwrap();
+            let (sim_b, _) = Simulation::from_seed(config(), 2).unwrap();
+
+            assert_ne!(
+                sim_a.sea().wastes()[0].position(),
+                sim_b.sea().wastes()[0].position(),
+            );
+        }
+
+        #[test]
+        fn test_invalid_config() {
+            let mut config = config();
+            config.eye.cells = 0;
+
+            let err = Simulation::from_seed(config, 1).err().unwrap();
+            assert_eq!(err.field, "eye.cells");
+        }
+    }
+
+    mod spatial_index {
+        use super::*;
+
+        #[test]
+        fn test_matches_brute_force() {
+            let run = |spatial_index| {
+                let config = SimulationConfig {
+                    spatial_index,
+                    ..config()
+                };
+
+                let (mut sim, mut rng) = Simulation::from_seed(config, 3).unwrap();
+
+                (0..3).map(|_| sim.train(&mut rng)).collect::<Vec<_>>()
+            };
+
+            assert_eq!(
+                run(SpatialIndexConfig::Grid { cells: 16 }),
+                run(SpatialIndexConfig::BruteForce),
+            );
+        }
+    }
+
+    mod evaluation {
+        use super::*;
+
+        fn isolated() -> SimulationConfig {
+            SimulationConfig {
+                evaluation: EvaluationConfig::Isolated { batch_size: 4 },
+                ..config()
+            }
+        }
+
+        #[test]
+        fn test_isolated() {
+            let (mut sim, mut rng) = Simulation::from_seed(isolated(), 5).unwrap();
+            let stats = sim.train(&mut rng);
+
+            assert_eq!(sim.generation(), 1);
+            assert_eq!(sim.sea().collectors().len(), 6);
+            assert!(stats.max_fitness() > 0.0);
+        }
+
+        #[test]
+        fn test_isolated_preview() {
+            let train = {
+                let (mut sim, mut rng) = Simulation::from_seed(isolated(), 5).unwrap();
+                (0..2).map(|_| sim.train(&mut rng)).collect::<Vec<_>>()
+            };
+
+            let step = {
+                let (mut sim, mut rng) = Simulation::from_seed(isolated(), 5).unwrap();
+
+                (0..2)
+                    .map(|_| loop {
+                        if let Some(stats) = sim.step(&mut rng) {
+                            break stats;
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(step, train);
+        }
+
+        #[test]
+        fn test_episodes() {
+            for evaluation in [
+                EvaluationConfig::Shared,
+                EvaluationConfig::Isolated { batch_size: 4 },
+            ] {
+                let config = SimulationConfig {
+                    evaluation,
+                    episodes: EpisodesConfig {
+                        count: 3,
+                        aggregation: AggregationConfig::Quantile { quantile: 0.5 },
+                    },
+                    ..config()
+                };
+
+                let (mut sim, mut rng) = Simulation::from_seed(config, 5).unwrap();
+                let stats = sim.train(&mut rng);
+
+                assert_eq!(sim.episode_scores().len(), 6);
+
+                let fitness = sim
+                    .episode_scores()
+                    .iter()
+                    .map(|scores| {
+                        assert_eq!(scores.len(), 3);
+
+                        let mut scores = scores.clone();
+                        scores.sort_by(|a, b| a.total_cmp(b));
+                        scores[1]
+                    })
+                    .fold(0.0, f32::max);
+
+                assert_eq!(stats.max_fitness(), fitness);
+            }
+        }
+
+        #[test]
+        fn test_fitness_function() {
+            struct Distance;
+
+            impl FitnessFunction for Distance {
+                fn score(&self, metrics: &EpisodeMetrics) -> f32 {
+                    metrics.distance
+                }
+            }
+
+            let (sim, mut rng) = Simulation::from_seed(config(), 5).unwrap();
+            let mut sim = sim.with_fitness_function(Distance);
+            let stats = sim.train(&mut rng);
+
+            let speed = sim.config().collector.speed_max;
+            let steps = (sim.config().generation_length + 1) as f32;
+
+            assert!(stats.min_fitness() > 0.0);
+            assert!(stats.max_fitness() <= speed * steps * 1.001);
+        }
+
+        #[test]
+        fn test_reproducible() {
+            let run = || {
+                let (mut sim, mut rng) = Simulation::from_seed(isolated(), 5).unwrap();
+                (0..2).map(|_| sim.train(&mut rng)).collect::<Vec<_>>()
+            };
+
+            assert_eq!(run(), run());
+        }
+    }
+
+    mod mutation {
+        use super::*;
+
+        fn config() -> SimulationConfig {
+            let mut config = super::config();
+
+            config.ga.mutation = MutationConfig::SelfAdaptive {
+                initial_step_size: 0.1,
+                min_step_size: 0.001,
+            };
+
+            config
+        }
+
+        #[test]
+        fn test_step_sizes_round_trip() {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 3).unwrap();
+
+            sim.train(&mut rng);
+            let first: Vec<_> =
+                sim.sea.collectors.iter().map(|c| c.brain.step_sizes.clone()).collect();
+
+            sim.train(&mut rng);
+            let second: Vec<_> =
+                sim.sea.collectors.iter().map(|c| c.brain.step_sizes.clone()).collect();
+
+            // Carried through the sea instead of starting over every generation
+            for (collector, step_sizes) in sim.sea.collectors.iter().zip(&second) {
+                assert_eq!(step_sizes.len(), collector.as_chromosome().len());
+                assert_eq!(collector.as_chromosome().step_sizes(), &step_sizes[..]);
+            }
+
+            assert_ne!(first, second);
+            assert!(second.iter().flatten().any(|&step_size| step_size != 0.1));
+
+            // And through a snapshot
+            let sim = Simulation::from_snapshot(sim.snapshot(), &mut rng).unwrap();
+
+            let restored: Vec<_> =
+                sim.sea.collectors.iter().map(|c| c.brain.step_sizes.clone()).collect();
+
+            assert_eq!(restored, second);
+        }
+    }
+
+    mod fuel {
+        use super::*;
+
+        fn config() -> SimulationConfig {
+            SimulationConfig {
+                fuel: FuelConfig {
+                    enabled: true,
+                    capacity: 0.05,
+                    refuel_rate: 0.01,
+                },
+                ports: PortsConfig {
+                    positions: vec![[0.5, 0.5]],
+                    radius: 0.05,
+                },
+                ..super::config()
+            }
+        }
+
+        #[test]
+        fn test_topology() {
+            let topology = Brain::topology(&config());
+            assert_eq!(topology[0].neurons, 10);
+        }
+
+        #[test]
+        fn test_running_dry() {
+            let config = SimulationConfig {
+                ports: PortsConfig::default(),
+                ..config()
+            };
+
+            let (mut sim, mut rng) = Simulation::from_seed(config, 1).unwrap();
+
+            for _ in 0..100 {
+                sim.step(&mut rng);
+            }
+
+            let positions: Vec<_> = sim
+                .sea()
+                .collectors()
+                .iter()
+                .map(|collector| {
+                    assert_eq!(collector.fuel(), 0.0);
+                    assert_eq!(collector.metrics().fuel, Some(0.0));
+                    assert!(collector.metrics().distance <= 0.05);
+
+                    collector.position()
+                })
+                .collect();
+
+            sim.step(&mut rng);
+
+            for (collector, position) in sim.sea().collectors().iter().zip(positions) {
+                assert_eq!(collector.position(), position);
+            }
+        }
+
+        #[test]
+        fn test_refuel() {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 1).unwrap();
+
+            let collector = &mut sim.sea.collectors[0];
+            collector.position = na::Point2::new(0.5, 0.5);
+            collector.fuel = 0.0;
+
+            sim.step(&mut rng);
+
+            let collector = &sim.sea().collectors()[0];
+            assert_eq!(collector.position(), na::Point2::new(0.5, 0.5));
+            assert_eq!(collector.fuel(), 0.01);
+        }
+    }
+
+    mod cargo {
+        use super::*;
+
+        fn config() -> SimulationConfig {
+            SimulationConfig {
+                cargo: CargoConfig {
+                    enabled: true,
+                    capacity: 2,
+                },
+                ports: PortsConfig {
+                    positions: vec![[0.5, 0.5]],
+                    radius: 0.05,
+                },
+                ..super::config()
+            }
+        }
+
+        #[test]
+        fn test_topology() {
+            let topology = Brain::topology(&config());
+            assert_eq!(topology[0].neurons, 12);
+        }
+
+        #[test]
+        fn test_full() {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 1).unwrap();
+
+            let waste = sim.sea.wastes[0].position;
+            let collector = &mut sim.sea.collectors[0];
+            collector.position = waste;
+            collector.cargo = 2;
+
+            sim.step(&mut rng);
+
+            let collector = &sim.sea().collectors()[0];
+            assert_eq!(collector.cargo(), 2);
+            assert_eq!(collector.metrics().wastes_collected, 0);
+            assert_eq!(sim.sea().wastes()[0].position(), waste);
+        }
+
+        #[test]
+        fn test_delivery() {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 1).unwrap();
+
+            let collector = &mut sim.sea.collectors[0];
+            collector.position = na::Point2::new(0.5, 0.5);
+            collector.cargo = 2;
+            collector.cargo_wastes = 2;
+            collector.cargo_value = 2.0;
+            collector.metrics.wastes_collected = 2;
+
+            sim.step(&mut rng);
+
+            let collector = &sim.sea().collectors()[0];
+            assert_eq!(collector.cargo(), 0);
+            assert_eq!(collector.metrics().wastes_delivered, 2);
+            assert_eq!(collector.metrics().cargo, Some(0));
+            assert_eq!(WastesCollected.score(collector.metrics()), 2.0);
+        }
+    }
+
+    mod categories {
+        use super::*;
+
+        fn config() -> SimulationConfig {
+            let mut config = SimulationConfig {
+                cargo: CargoConfig {
+                    enabled: true,
+                    capacity: 2,
+                },
+                ..super::config()
+            };
+
+            config.eye.per_category = true;
+            config.wastes.categories = vec![
+                WasteCategoryConfig {
+                    name: "bottle".into(),
+                    ..Default::default()
+                },
+                WasteCategoryConfig {
+                    name: "net".into(),
+                    value: 5.0,
+                    mass: 3,
+                    ..Default::default()
+                },
+            ];
+
+            config
+        }
+
+        #[test]
+        fn test_topology() {
+            let topology = Brain::topology(&config());
+            assert_eq!(topology[0].neurons, 2 * 9 + 3);
+        }
+
+        #[test]
+        fn test_value() {
+            let mut config = config();
+            config.cargo.capacity = 3;
+
+            let (mut sim, mut rng) = Simulation::from_seed(config, 1).unwrap();
+            sim.sea.wastes[0].category = 1;
+
+            let waste = sim.sea.wastes[0].position;
+            let collector = &mut sim.sea.collectors[0];
+            collector.position = waste;
+
+            sim.step(&mut rng);
+
+            let collector = &sim.sea().collectors()[0];
+            assert_eq!(collector.cargo(), 3);
+            assert_eq!(collector.cargo_value, 5.0);
+            assert_eq!(collector.metrics().value_collected, 5.0);
+        }
+
+        #[test]
+        fn test_too_heavy() {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 1).unwrap();
+            sim.sea.wastes[0].category = 1;
+
+            let waste = sim.sea.wastes[0].position;
+            let collector = &mut sim.sea.collectors[0];
+            collector.position = waste;
+
+            sim.step(&mut rng);
+
+            let collector = &sim.sea().collectors()[0];
+            assert_eq!(collector.cargo(), 0);
+            assert_eq!(sim.sea().wastes()[0].position(), waste);
+        }
+    }
+
+    mod obstacles {
+        use super::*;
+
+        #[test]
+        fn test_island() {
+            let mut config = config();
+            config.obstacles.polygons = vec![ObstacleConfig {
+                kind: ObstacleKind::Island,
+                points: vec![[0.4, 0.0], [0.6, 0.0], [0.6, 1.0], [0.4, 1.0]],
+            }];
+
+            let (mut sim, mut rng) = Simulation::from_seed(config, 1).unwrap();
+            let island = &sim.sea().obstacles()[0].clone();
+
+            for _ in 0..sim.config().generation_length {
+                sim.step(&mut rng);
+
+                assert!(sim
+                    .sea()
+                    .collectors()
+                    .iter()
+                    .all(|collector| !island.contains(collector.position())));
+
+                assert!(sim
+                    .sea()
+                    .wastes()
+                    .iter()
+                    .all(|waste| !island.contains(waste.position())));
+            }
+
+            let collisions: usize = sim
+                .sea()
+                .collectors()
+                .iter()
+                .map(|collector| collector.metrics().collisions)
+                .sum();
+
+            assert!(collisions > 0);
+        }
+    }
+
+    mod boundary {
+        use super::*;
+
+        #[test]
+        fn test_wall() {
+            let config = SimulationConfig {
+                boundary: BoundaryConfig::Wall { penalty: 0.5 },
+                ..config()
+            };
+
+            let (mut sim, mut rng) = Simulation::from_seed(config, 1).unwrap();
+
+            for _ in 0..sim.config().generation_length {
+                sim.step(&mut rng);
+
+                for collector in sim.sea().collectors() {
+                    let position = collector.position();
+                    assert!((0.0..=1.0).contains(&position.x));
+                    assert!((0.0..=1.0).contains(&position.y));
+                }
+            }
+
+            let wall_hits: usize = sim
+                .sea()
+                .collectors()
+                .iter()
+                .map(|collector| collector.metrics().wall_hits)
+                .sum();
+
+            assert!(wall_hits > 0);
+        }
+    }
+
+    mod fleet {
+        use super::*;
+
+        fn config() -> SimulationConfig {
+            let mut config = SimulationConfig {
+                separation: SeparationConfig {
+                    enabled: true,
+                    radius: 0.05,
+                },
+                ..super::config()
+            };
+
+            config.eye.collectors = true;
+            config.eye.headings = true;
+            config
+        }
+
+        #[test]
+        fn test_topology() {
+            let topology = Brain::topology(&config());
+            assert_eq!(topology[0].neurons, 3 * 9);
+        }
+
+        #[test]
+        fn test_separation() {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 1).unwrap();
+
+            sim.sea.collectors[0].position = na::Point2::new(0.5, 0.5);
+            sim.sea.collectors[1].position = na::Point2::new(0.5, 0.51);
+            sim.step(&mut rng);
+
+            let collectors = sim.sea().collectors();
+            let distance = na::distance(&collectors[0].position(), &collectors[1].position());
+
+            assert!(distance >= 0.05 - 1e-5);
+            assert_eq!(collectors[0].metrics().collisions, 1);
+            assert_eq!(collectors[1].metrics().collisions, 1);
+        }
+    }
+
+    mod sensors {
+        use super::*;
+
+        #[test]
+        fn test_rays() {
+            let mut config = config();
+            config.eye.sensor = SensorConfig::Rays {
+                channels: RayChannelConfig::all(),
+                collector_radius: 0.01,
+            };
+
+            assert_eq!(Brain::topology(&config)[0].neurons, 4 * 9);
+
+            let (mut sim, mut rng) = Simulation::from_seed(config, 1).unwrap();
+            sim.train(&mut rng);
+        }
+
+        #[test]
+        fn test_senses() {
+            let mut config = config();
+            let eye = config.eye.cells;
+
+            config.senses = SensesConfig {
+                port: Some(true),
+                speed: true,
+                heading: true,
+                position: true,
+                time: true,
+                ..Default::default()
+            };
+
+            assert_eq!(Brain::topology(&config)[0].neurons, eye + 8);
+
+            let (mut sim, mut rng) = Simulation::from_seed(config, 1).unwrap();
+            sim.train(&mut rng);
+        }
+
+        #[test]
+        fn test_noise() {
+            let mut config = config();
+
+            config.eye.noise = NoiseConfig {
+                std_dev: 0.05,
+                detection: 0.5,
+                dropout: 0.1,
+                sea_state: 0.2,
+            };
+
+            let run = || {
+                let (mut sim, mut rng) = Simulation::from_seed(config.clone(), 7).unwrap();
+                (0..2).map(|_| sim.train(&mut rng)).collect::<Vec<_>>()
+            };
+
+            assert_eq!(run(), run());
+        }
+    }
+
+    mod physics {
+        use super::*;
+
+        #[test]
+        fn test_champion() {
+            let mut config = config();
+            config.physics.enabled = true;
+
+            let (mut sim, mut rng) = Simulation::from_seed(config, 3).unwrap();
+            let stats = sim.train(&mut rng);
+
+            let champion = sim.champion_snapshot().unwrap();
+            assert_eq!(champion.config_hash, sim.config().hash());
+            assert_eq!(champion.fitness, Some(stats.max_fitness()));
+
+            let champion = BrainSnapshot::from_json(&champion.to_json().unwrap()).unwrap();
+            sim.replay_brain(&champion, &mut rng).unwrap();
+
+            for _ in 0..50 {
+                sim.step(&mut rng);
+            }
+
+            let top_speed = sim.config().physics.top_speed();
+
+            for collector in sim.sea().collectors() {
+                let weights: Vec<_> = collector.brain.nn.weights().collect();
+                assert_eq!(weights, champion.weights);
+
+                // Under way, yet held below top speed by drag
+                assert!(collector.metrics().distance > 0.0);
+                assert!(collector.speed <= top_speed + 1e-6);
+            }
+        }
+    }
+
+    mod currents {
+        use super::*;
+
+        #[test]
+        fn test_drift() {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 1).unwrap();
+            sim.set_currents(CurrentsConfig::Uniform { u: 0.01, v: 0.0 });
+
+            let before: Vec<_> = sim.sea().wastes().iter().map(Waste::position).collect();
+            sim.step(&mut rng);
+
+            let drifted = sim
+                .sea()
+                .wastes()
+                .iter()
+                .zip(before)
+                .filter(|(waste, before)| {
+                    let offset = waste.position() - before;
+                    (offset.x - 0.01).abs() < 1e-6 && offset.y == 0.0
+                })
+                .count();
+
+            // Save for the ones picked up (and respawned) along the way
+            assert!(drifted >= 18);
+
+            // Drifting is not travelling
+            let collector = &sim.sea().collectors()[0];
+            assert!(collector.metrics().distance <= sim.config().collector.speed_max);
+        }
+    }
+
+    mod wastes {
+        use super::*;
+
+        #[test]
+        fn test_finite_supply() {
+            let mut config = config();
+            config.wastes.respawn = RespawnConfig::None;
+
+            let (mut sim, mut rng) = Simulation::from_seed(config, 1).unwrap();
+
+            for _ in 0..sim.config().generation_length {
+                sim.step(&mut rng);
+            }
+
+            let collected: usize = sim
+                .sea()
+                .collectors()
+                .iter()
+                .map(|collector| collector.metrics().wastes_collected)
+                .sum();
+
+            assert!(collected > 0);
+            assert_eq!(sim.sea().wastes().len() + collected, sim.config().waste_count);
+
+            // A new generation starts with a full sea again
+            sim.step(&mut rng);
+            assert_eq!(sim.sea().wastes().len(), sim.config().waste_count);
+        }
+    }
+
+    mod snapshot {
+        use super::*;
+
+        fn trained() -> (Simulation, ChaCha8Rng) {
+            let (mut sim, mut rng) = Simulation::from_seed(config(), 7).unwrap();
+            sim.train(&mut rng);
+            sim.train(&mut rng);
+            (sim, rng)
+        }
+
+        fn brains(sim: &Simulation) -> Vec<Vec<f32>> {
+            sim.sea()
+                .collectors()
+                .iter()
+                .map(|collector| collector.brain.nn.weights().collect())
+                .collect()
+        }
+
+        #[test]
+        fn test_json() {
+            let (sim, mut rng) = trained();
+
+            let json = sim.snapshot().to_json().unwrap();
+            let snapshot = PopulationSnapshot::from_json(&json).unwrap();
+            let resumed = Simulation::from_snapshot(snapshot, &mut rng).unwrap();
+
+            assert_eq!(brains(&resumed), brains(&sim));
+            assert_eq!(resumed.generation(), 2);
+            assert_eq!(
+                resumed.hall_of_fame().champion().unwrap().chromosome(),
+                sim.hall_of_fame().champion().unwrap().chromosome(),
+            );
+        }
+
+        #[test]
+        fn test_bytes() {
+            let (sim, mut rng) = trained();
+
+            let bytes = sim.snapshot().to_bytes().unwrap();
+            let snapshot = PopulationSnapshot::from_bytes(&bytes).unwrap();
+
+            assert_eq!(snapshot.config_hash, sim.config().hash());
+
+            let resumed = Simulation::from_snapshot(snapshot, &mut rng).unwrap();
+
+            assert_eq!(brains(&resumed), brains(&sim));
+        }
+
+        #[test]
+        fn test_champion() {
+            let (mut sim, mut rng) = trained();
+            let champion = sim.champion_snapshot().unwrap();
+
+            assert_eq!(champion.topology.len(), 3);
+            assert!(champion.fitness.is_some());
+
+            let json = champion.to_json().unwrap();
+            let champion = BrainSnapshot::from_json(&json).unwrap();
+
+            sim.replay_brain(&champion, &mut rng).unwrap();
+
+            for brain in brains(&sim) {
+                assert_eq!(brain, champion.weights);
+            }
+        }
+
+        #[test]
+        fn test_replay_keeps_population() {
+            let (mut sim, mut rng) = trained();
+            let population = brains(&sim);
+            let generation = sim.generation();
+
+            assert!(sim.replay_champion(&mut rng));
+            assert!(sim.is_replaying());
+            assert_ne!(brains(&sim), population);
+
+            for _ in 0..=sim.config().generation_length {
+                assert!(sim.step(&mut rng).is_none());
+            }
+
+            assert!(!sim.is_replaying());
+            assert_eq!(brains(&sim), population);
+            assert_eq!(sim.generation(), generation);
+        }
+
+        #[test]
+        fn test_unsupported_version() {
+            let (sim, _) = trained();
+            let json = sim
+                .champion_snapshot()
+                .unwrap()
+                .to_json()
+                .unwrap()
+                .replacen(r#""version":1"#, r#""version":99"#, 1);
+
+            assert!(matches!(
+                BrainSnapshot::from_json(&json),
+                Err(SnapshotError::UnsupportedVersion(99)),
+            ));
+
+            let mut bytes = sim.snapshot().to_bytes().unwrap();
+            bytes[4] = 99;
+
+            assert!(matches!(
+                PopulationSnapshot::from_bytes(&bytes),
+                Err(SnapshotError::UnsupportedVersion(99)),
+            ));
+        }
+
+        #[test]
+        fn test_invalid_topology() {
+            let (sim, mut rng) = trained();
+            let mut snapshot = sim.snapshot();
+            snapshot.config.eye.cells = 3;
+
+            assert!(matches!(
+                Simulation::from_snapshot(snapshot, &mut rng),
+                Err(SnapshotError::InvalidTopology { .. }),
+            ));
+        }
+
+        #[test]
+        fn test_invalid_config() {
+            let (sim, mut rng) = trained();
+            let mut snapshot = sim.snapshot();
+            snapshot.config.episodes.count = 0;
+
+            assert!(matches!(
+                Simulation::from_snapshot(snapshot, &mut rng),
+                Err(SnapshotError::Config(_)),
+            ));
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Transcendental functions on the simulation's path, computed by `libm`
+//! instead of the platform (or `nalgebra`, which defers to it), so that a
+//! seeded run plays out bit for bit the same on native and WASM targets.
+
+use crate::*;
+use std::f32::consts::*;
+
+pub(crate) fn sin_cos(x: f32) -> (f32, f32) {
+    (libm::sinf(x), libm::cosf(x))
+}
+
+pub(crate) fn exp(x: f32) -> f32 {
+    libm::expf(x)
+}
+
+/// Rotation by `angle` radians, as `na::Rotation2::new()` builds it
+pub(crate) fn rotation(angle: f32) -> na::Rotation2<f32> {
+    let (sin, cos) = sin_cos(angle);
+    na::Rotation2::from_matrix_unchecked(na::Matrix2::new(cos, -sin, sin, cos))
+}
+
+/// Uniformly random rotation
+pub(crate) fn random_rotation(rng: &mut dyn RngCore) -> na::Rotation2<f32> {
+    rotation(rng.gen::<f32>() * 2.0 * PI)
+}
+
+/// Angle of `rotation`, from -PI to PI, as `na::Rotation2::angle()` gives it
+pub(crate) fn angle(rotation: na::Rotation2<f32>) -> f32 {
+    let matrix = rotation.matrix();
+    libm::atan2f(matrix[(1, 0)], matrix[(0, 0)])
+}
+
+/// Angle turning `a` toward `b`, from -PI to PI, as
+/// `na::Rotation2::rotation_between(a, b).angle()` gives it
+pub(crate) fn angle_between(a: na::Vector2<f32>, b: na::Vector2<f32>) -> f32 {
+    libm::atan2f(a.perp(&b), a.dot(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_matches_nalgebra() {
+        for radians in [-3.0, -FRAC_PI_2, -0.3, 0.0, 0.7, FRAC_PI_2, 2.5] {
+            let ours = rotation(radians);
+            let theirs = na::Rotation2::new(radians);
+
+            for (a, b) in ours.matrix().iter().zip(theirs.matrix().iter()) {
+                assert_relative_eq!(a, b, epsilon = 1e-6);
+            }
+
+            assert_relative_eq!(angle(theirs), theirs.angle(), epsilon = 1e-6);
+
+            let b = theirs * na::Vector2::new(0.3, 0.8);
+            let between = na::Rotation2::rotation_between(&na::Vector2::y(), &b).angle();
+            assert_relative_eq!(angle_between(na::Vector2::y(), b), between, epsilon = 1e-6);
+        }
+    }
+}
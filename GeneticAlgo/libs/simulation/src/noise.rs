@@ -0,0 +1,113 @@
+use crate::*;
+
+impl NoiseConfig {
+    /// Whether the eye sees things as they are
+    pub fn is_exact(&self) -> bool {
+        self.std_dev == 0.0 && self.dropout == 0.0 && !self.hides_wastes()
+    }
+
+    /// Whether some wastes within the field of view may go unnoticed
+    pub fn hides_wastes(&self) -> bool {
+        self.detection < 1.0 || self.sea_state > 0.0
+    }
+
+    /// Chance of spotting a waste `distance` away, falling from 1.0 right
+    /// next to the collector down to `detection` at `fov_range`, and to
+    /// nothing beyond what the waves let through
+    pub fn detection(&self, distance: f32, fov_range: f32) -> f32 {
+        if distance > fov_range * (1.0 - self.sea_state) {
+            return 0.0;
+        }
+
+        1.0 - (1.0 - self.detection) * distance / fov_range
+    }
+
+    /// Wastes the collector at `position` happens to spot this step, out of
+    /// those its eye could see
+    pub(crate) fn detect<'a>(
+        &self,
+        position: na::Point2<f32>,
+        surroundings: &Surroundings<'a>,
+        config: &SimulationConfig,
+        rng: &mut dyn RngCore,
+    ) -> Vec<Waste> {
+        let fov_range = config.eye.fov_range;
+
+        let reach = fov_range
+            + config
+                .wastes
+                .categories
+                .iter()
+                .map(|category| category.radius(config))
+                .fold(0.0, f32::max);
+
+        surroundings
+            .wastes_near(position, reach)
+            .filter(|waste| {
+                let distance = config.boundary.distance(position, waste.position);
+                rng.gen_bool(self.detection(distance, fov_range).clamp(0.0, 1.0) as f64)
+            })
+            .map(|waste| Waste::new(waste.position, waste.category))
+            .collect()
+    }
+
+    /// Drops the whole frame now and then, blurs what is left
+    pub(crate) fn apply(&self, outputs: &mut [f32], rng: &mut dyn RngCore) {
+        if self.dropout > 0.0 && rng.gen_bool(self.dropout as f64) {
+            outputs.fill(0.0);
+            return;
+        }
+
+        if self.std_dev > 0.0 {
+            for output in outputs {
+                *output += self.std_dev * ga::standard_normal(rng);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_detection() {
+        let noise = NoiseConfig {
+            detection: 0.5,
+            sea_state: 0.2,
+            ..Default::default()
+        };
+
+        assert_relative_eq!(noise.detection(0.0, 0.25), 1.0);
+        assert_relative_eq!(noise.detection(0.1, 0.25), 0.8);
+        assert_eq!(noise.detection(0.21, 0.25), 0.0);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut outputs = vec![0.5; 100];
+
+        NoiseConfig::default().apply(&mut outputs, &mut rng);
+        assert!(outputs.iter().all(|&output| output == 0.5));
+
+        let noise = NoiseConfig {
+            std_dev: 0.1,
+            ..Default::default()
+        };
+
+        noise.apply(&mut outputs, &mut rng);
+        let mean = outputs.iter().sum::<f32>() / 100.0;
+        assert!(outputs.iter().any(|&output| output != 0.5));
+        assert_relative_eq!(mean, 0.5, epsilon = 0.05);
+
+        let noise = NoiseConfig {
+            dropout: 1.0,
+            ..Default::default()
+        };
+
+        noise.apply(&mut outputs, &mut rng);
+        assert!(outputs.iter().all(|&output| output == 0.0));
+    }
+}
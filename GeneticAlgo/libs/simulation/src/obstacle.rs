@@ -0,0 +1,418 @@
+use crate::*;
+use std::fmt;
+
+/// How far from an obstacle's edge a collector stops, so that it does not
+/// end up on (or, through rounding, across) the edge itself
+const CLEARANCE: f32 = 1e-4;
+
+/// How many random spots are tried before giving up on finding one that
+/// lies outside of every obstacle
+const ATTEMPTS: usize = 100;
+
+/// Static polygon within the sea; built from an `ObstacleConfig`.
+///
+/// Unlike everything else, obstacles do not wrap around the edges of the
+/// torus - a coastline along an edge has to be given on both sides.
+#[derive(Clone, Debug)]
+pub struct Obstacle {
+    kind: ObstacleKind,
+    points: Vec<na::Point2<f32>>,
+}
+
+#[derive(Debug)]
+pub enum ObstaclesError {
+    InvalidLine { line: usize, content: String },
+    UnknownKind { line: usize, kind: String },
+    TooFewPoints { line: usize },
+}
+
+impl Obstacle {
+    pub fn from_config(config: &ObstacleConfig) -> Self {
+        assert!(config.points.len() >= 3);
+
+        Self {
+            kind: config.kind,
+            points: config
+                .points
+                .iter()
+                .map(|&[x, y]| na::Point2::new(x, y))
+                .collect(),
+        }
+    }
+
+    pub fn kind(&self) -> ObstacleKind {
+        self.kind
+    }
+
+    pub fn points(&self) -> &[na::Point2<f32>] {
+        &self.points
+    }
+
+    /// Land hides wastes behind it and keeps wastes out; exclusion zones
+    /// only keep collectors out
+    pub fn is_land(&self) -> bool {
+        self.kind != ObstacleKind::Exclusion
+    }
+
+    pub fn contains(&self, point: na::Point2<f32>) -> bool {
+        let mut inside = false;
+
+        for (a, b) in self.edges() {
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+            {
+                inside = !inside;
+            }
+        }
+
+        inside
+    }
+
+    /// First point where the segment from `from` to `to` crosses the
+    /// obstacle's outline, as a share of the segment, together with the
+    /// unit normal of the edge crossed
+    pub fn crossing(
+        &self,
+        from: na::Point2<f32>,
+        to: na::Point2<f32>,
+    ) -> Option<(f32, na::Vector2<f32>)> {
+        let r = to - from;
+
+        self.edges()
+            .filter_map(|(a, b)| {
+                let s = b - a;
+                let denom = cross(r, s);
+
+                if denom.abs() < f32::EPSILON {
+                    return None;
+                }
+
+                let t = cross(a - from, s) / denom;
+                let u = cross(a - from, r) / denom;
+
+                if !(0.0..=1.0).contains(&t) || !(0.0..=1.0).contains(&u) {
+                    return None;
+                }
+
+                Some((t, na::Vector2::new(-s.y, s.x).normalize()))
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (na::Point2<f32>, na::Point2<f32>)> + '_ {
+        self.points
+            .iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+}
+
+/// Nearest crossing of the segment from `from` to `to` through any of
+/// `obstacles` - including, on a torus, the part of the segment that comes
+/// back through the opposite edge
+pub(crate) fn first_crossing<'a>(
+    obstacles: impl IntoIterator<Item = &'a Obstacle>,
+    from: na::Point2<f32>,
+    to: na::Point2<f32>,
+    boundary: &BoundaryConfig,
+) -> Option<(f32, na::Vector2<f32>)> {
+    let shifts = shifts(from, to, boundary);
+
+    obstacles
+        .into_iter()
+        .flat_map(|obstacle| {
+            shifts
+                .iter()
+                .filter_map(move |shift| obstacle.crossing(from + shift, to + shift))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+}
+
+/// Shifts bringing each part of the segment from `from` to `to` that lies
+/// past an edge of the torus back into the sea
+fn shifts(
+    from: na::Point2<f32>,
+    to: na::Point2<f32>,
+    boundary: &BoundaryConfig,
+) -> Vec<na::Vector2<f32>> {
+    if !boundary.is_toroidal() {
+        return vec![na::Vector2::zeros()];
+    }
+
+    let shifts = |a: f32, b: f32| {
+        let mut shifts = vec![0.0];
+
+        if a.min(b) < 0.0 {
+            shifts.push(1.0);
+        }
+
+        if a.max(b) > 1.0 {
+            shifts.push(-1.0);
+        }
+
+        shifts
+    };
+
+    let ys = shifts(from.y, to.y);
+
+    shifts(from.x, to.x)
+        .into_iter()
+        .flat_map(|x| ys.iter().map(move |&y| na::Vector2::new(x, y)))
+        .collect()
+}
+
+/// Where a collector moving from `from` by `delta` ends up when it bumps
+/// into an obstacle, `None` if its way is clear; the position may lie past
+/// an edge, for the boundary to confine
+pub(crate) fn travel(
+    obstacles: &[Obstacle],
+    from: na::Point2<f32>,
+    delta: na::Vector2<f32>,
+    collision: CollisionConfig,
+    boundary: &BoundaryConfig,
+) -> Option<na::Point2<f32>> {
+    let (t, normal) = first_crossing(obstacles, from, from + delta, boundary)?;
+    let contact = from + delta * backoff(t, delta);
+
+    match collision {
+        CollisionConfig::Stop => Some(contact),
+
+        // What is left of the move, minus what points into the obstacle
+        CollisionConfig::Slide => {
+            let rest = delta * (1.0 - t);
+            let slide = rest - normal * normal.dot(&rest);
+
+            match first_crossing(obstacles, contact, contact + slide, boundary) {
+                None => Some(contact + slide),
+                Some((t, _)) => Some(contact + slide * backoff(t, slide)),
+            }
+        }
+    }
+}
+
+/// Picks new spots from `sample` until one lies outside of `obstacles`
+pub(crate) fn relocate<'a>(
+    obstacles: impl IntoIterator<Item = &'a Obstacle> + Clone,
+    position: &mut na::Point2<f32>,
+    mut sample: impl FnMut() -> na::Point2<f32>,
+) {
+    let blocked = |position| {
+        obstacles
+            .clone()
+            .into_iter()
+            .any(|obstacle| obstacle.contains(position))
+    };
+
+    for _ in 0..ATTEMPTS {
+        if !blocked(*position) {
+            return;
+        }
+
+        *position = sample();
+    }
+}
+
+fn backoff(t: f32, delta: na::Vector2<f32>) -> f32 {
+    (t - CLEARANCE / delta.norm()).max(0.0)
+}
+
+fn cross(a: na::Vector2<f32>, b: na::Vector2<f32>) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+impl ObstacleConfig {
+    /// Parses a polygon file, one obstacle per line as its kind followed by
+    /// its points, e.g. `island 0.2,0.2 0.3,0.2 0.25,0.3`; blank lines and
+    /// lines starting with `#` are skipped
+    pub fn from_polygons(text: &str) -> Result<Vec<Self>, ObstaclesError> {
+        let mut obstacles = Vec::new();
+
+        for (line, content) in text.lines().enumerate() {
+            let content = content.trim();
+            let line = line + 1;
+
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = content.split_whitespace();
+
+            let kind = match fields.next() {
+                Some("island") => ObstacleKind::Island,
+                Some("coastline") => ObstacleKind::Coastline,
+                Some("exclusion") => ObstacleKind::Exclusion,
+                kind => {
+                    return Err(ObstaclesError::UnknownKind {
+                        line,
+                        kind: kind.unwrap_or_default().to_string(),
+                    })
+                }
+            };
+
+            let points = fields
+                .map(|point| {
+                    let (x, y) = point.split_once(',')?;
+                    Some([x.trim().parse().ok()?, y.trim().parse().ok()?])
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| ObstaclesError::InvalidLine {
+                    line,
+                    content: content.to_string(),
+                })?;
+
+            if points.len() < 3 {
+                return Err(ObstaclesError::TooFewPoints { line });
+            }
+
+            obstacles.push(Self { kind, points });
+        }
+
+        Ok(obstacles)
+    }
+}
+
+impl fmt::Display for ObstaclesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLine { line, content } => write!(
+                f,
+                "line {}: expected `kind x,y x,y x,y ...`, got `{}`",
+                line, content,
+            ),
+            Self::UnknownKind { line, kind } => write!(
+                f,
+                "line {}: unknown obstacle `{}`, expected `island`, `coastline` or `exclusion`",
+                line, kind,
+            ),
+            Self::TooFewPoints { line } => {
+                write!(f, "line {}: a polygon needs at least 3 points", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObstaclesError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// Square spanning from 0.4 to 0.6
+    fn island() -> Obstacle {
+        Obstacle::from_config(&ObstacleConfig {
+            kind: ObstacleKind::Island,
+            points: vec![[0.4, 0.4], [0.6, 0.4], [0.6, 0.6], [0.4, 0.6]],
+        })
+    }
+
+    #[test]
+    fn test_contains() {
+        let island = island();
+
+        assert!(island.contains(na::Point2::new(0.5, 0.5)));
+        assert!(!island.contains(na::Point2::new(0.3, 0.5)));
+        assert!(!island.contains(na::Point2::new(0.5, 0.7)));
+    }
+
+    #[test]
+    fn test_crossing() {
+        let island = island();
+
+        let (t, normal) = island
+            .crossing(na::Point2::new(0.2, 0.5), na::Point2::new(0.5, 0.5))
+            .unwrap();
+
+        assert_relative_eq!(t, 2.0 / 3.0, epsilon = 1e-5);
+        assert_relative_eq!(normal.x.abs(), 1.0);
+
+        assert!(island
+            .crossing(na::Point2::new(0.2, 0.3), na::Point2::new(0.8, 0.3))
+            .is_none());
+    }
+
+    #[test]
+    fn test_travel() {
+        let obstacles = [island()];
+        let from = na::Point2::new(0.3, 0.45);
+        let delta = na::Vector2::new(0.2, 0.1);
+
+        let boundary = BoundaryConfig::Toroidal;
+
+        assert!(travel(&obstacles, from, -delta, CollisionConfig::Stop, &boundary).is_none());
+
+        let stop = travel(&obstacles, from, delta, CollisionConfig::Stop, &boundary).unwrap();
+        assert_relative_eq!(stop.x, 0.4, epsilon = 1e-3);
+        assert!(stop.x < 0.4);
+
+        // Sliding along the western edge, keeping the vertical move
+        let slide = travel(&obstacles, from, delta, CollisionConfig::Slide, &boundary).unwrap();
+        assert_relative_eq!(slide.x, 0.4, epsilon = 1e-3);
+        assert_relative_eq!(slide.y, 0.55, epsilon = 1e-3);
+        assert!(!obstacles[0].contains(slide));
+    }
+
+    #[test]
+    fn test_travel_across_edges() {
+        // Hugging the western edge
+        let obstacles = [Obstacle::from_config(&ObstacleConfig {
+            kind: ObstacleKind::Island,
+            points: vec![[0.0, 0.4], [0.1, 0.4], [0.1, 0.6], [0.0, 0.6]],
+        })];
+
+        let from = na::Point2::new(0.95, 0.5);
+        let delta = na::Vector2::new(0.1, 0.0);
+
+        let stop = travel(
+            &obstacles,
+            from,
+            delta,
+            CollisionConfig::Stop,
+            &BoundaryConfig::Toroidal,
+        )
+        .unwrap();
+
+        assert_relative_eq!(stop.x, 1.0, epsilon = 1e-3);
+        assert!(stop.x < 1.0);
+
+        // Without a torus, the island is out of reach
+        assert!(travel(
+            &obstacles,
+            from,
+            delta,
+            CollisionConfig::Stop,
+            &BoundaryConfig::Reflective,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_polygons() {
+        let obstacles = ObstacleConfig::from_polygons(
+            "# Two obstacles\nisland 0.2,0.2 0.3,0.2 0.25,0.3\n\nexclusion 0,0 0.1,0 0.1,0.1 0,0.1\n",
+        )
+        .unwrap();
+
+        assert_eq!(obstacles.len(), 2);
+        assert_eq!(obstacles[0].kind, ObstacleKind::Island);
+        assert_eq!(obstacles[1].points[2], [0.1, 0.1]);
+    }
+
+    #[test]
+    fn test_polygons_errors() {
+        assert!(matches!(
+            ObstacleConfig::from_polygons("volcano 0,0 1,0 1,1\n"),
+            Err(ObstaclesError::UnknownKind { line: 1, .. }),
+        ));
+
+        assert!(matches!(
+            ObstacleConfig::from_polygons("island 0,0 1,0 1;1\n"),
+            Err(ObstaclesError::InvalidLine { line: 1, .. }),
+        ));
+
+        assert!(matches!(
+            ObstacleConfig::from_polygons("\nisland 0,0 1,0\n"),
+            Err(ObstaclesError::TooFewPoints { line: 2 }),
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::*;
+
+impl PhysicsConfig {
+    /// Turns the brain's response into thrust and rudder, and lets them act
+    /// on the collector's speed and spin for one timestep; returns the
+    /// angle turned by, in radians
+    pub(crate) fn steer(&self, collector: &mut Collector, response: &[f32]) -> f32 {
+        let throttle = response[0].clamp(-1.0, 1.0);
+        let rudder = response[1].clamp(-1.0, 1.0);
+
+        let force = throttle * self.thrust - self.linear_drag * collector.speed;
+        collector.speed = (collector.speed + force / self.mass * self.timestep).max(0.0);
+
+        // Angular drag pulls the spin toward whatever the rudder asks for,
+        // the more sluggishly the heavier the collector
+        let pull = (self.angular_drag / self.mass * self.timestep).min(1.0);
+        let spin = collector.spin + (rudder * self.max_turn_rate - collector.spin) * pull;
+
+        collector.spin = spin.clamp(-self.max_turn_rate, self.max_turn_rate);
+
+        let rotation = collector.spin * self.timestep;
+        collector.rotation = math::rotation(math::angle(collector.rotation) + rotation);
+
+        rotation
+    }
+
+    /// Speed at which drag balances full throttle; infinite without drag
+    pub fn top_speed(&self) -> f32 {
+        self.thrust / self.linear_drag
+    }
+
+    /// Distance covered in one timestep at `speed`
+    pub(crate) fn distance(&self, speed: f32) -> f32 {
+        speed * self.timestep
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn collector(config: &SimulationConfig) -> Collector {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut collector = Collector::random(config, &mut rng);
+        collector.speed = 0.0;
+        collector.rotation = na::Rotation2::new(0.0);
+        collector
+    }
+
+    #[test]
+    fn test_terminal_speed() {
+        let physics = PhysicsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+
+        let mut collector = collector(&SimulationConfig::default());
+
+        physics.steer(&mut collector, &[1.0, 0.0]);
+        let first = collector.speed;
+        assert!(first > 0.0);
+        assert!(first < physics.thrust / physics.linear_drag);
+
+        for _ in 0..1000 {
+            physics.steer(&mut collector, &[1.0, 0.0]);
+        }
+
+        assert_relative_eq!(
+            collector.speed,
+            physics.thrust / physics.linear_drag,
+            epsilon = 1e-5,
+        );
+
+        // Coasting, drag slows the collector down without reversing it
+        for _ in 0..1000 {
+            physics.steer(&mut collector, &[-1.0, 0.0]);
+        }
+
+        assert_eq!(collector.speed, 0.0);
+    }
+
+    #[test]
+    fn test_turn_rate() {
+        let physics = PhysicsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+
+        let mut collector = collector(&SimulationConfig::default());
+
+        let first = physics.steer(&mut collector, &[0.0, 1.0]);
+        assert!(first > 0.0);
+        assert!(first < physics.max_turn_rate * physics.timestep);
+
+        for _ in 0..100 {
+            physics.steer(&mut collector, &[0.0, 5.0]);
+        }
+
+        assert_relative_eq!(collector.spin, physics.max_turn_rate, epsilon = 1e-5);
+    }
+}
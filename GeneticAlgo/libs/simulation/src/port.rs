@@ -0,0 +1,124 @@
+use crate::*;
+use std::f32::consts::*;
+
+/// A base where collectors refuel and unload their cargo
+#[derive(Debug)]
+pub struct Port {
+    pub(crate) position: na::Point2<f32>,
+}
+
+impl Port {
+    pub fn new(position: na::Point2<f32>) -> Self {
+        Self { position }
+    }
+
+    pub fn position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    pub(crate) fn nearest<'a>(
+        ports: &'a [Port],
+        position: na::Point2<f32>,
+        boundary: &BoundaryConfig,
+    ) -> Option<&'a Port> {
+        ports.iter().min_by(|a, b| {
+            boundary
+                .distance(a.position, position)
+                .total_cmp(&boundary.distance(b.position, position))
+        })
+    }
+
+    /// Direction of the port as seen by a collector (relative to its
+    /// heading, from -1.0 to 1.0 for -PI to PI) and distance to it
+    pub(crate) fn bearing(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        boundary: &BoundaryConfig,
+    ) -> (f32, f32) {
+        let vec = boundary.offset(position, self.position);
+
+        let angle = math::angle_between(na::Vector2::y(), vec);
+        let angle = na::wrap(angle - math::angle(rotation), -PI, PI);
+
+        (angle / PI, vec.norm())
+    }
+
+    /// Whether a collector at `position` is docked at this port
+    pub(crate) fn reaches(&self, position: na::Point2<f32>, config: &SimulationConfig) -> bool {
+        config.boundary.distance(self.position, position) <= config.ports.radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_bearing() {
+        let port = Port::new(na::Point2::new(0.5, 0.7));
+        let position = na::Point2::new(0.5, 0.5);
+
+        let boundary = BoundaryConfig::Toroidal;
+
+        let (direction, distance) = port.bearing(position, na::Rotation2::new(0.0), &boundary);
+        assert_relative_eq!(direction, 0.0);
+        assert_relative_eq!(distance, 0.2);
+
+        let (direction, _) = port.bearing(position, na::Rotation2::new(FRAC_PI_2), &boundary);
+        assert_relative_eq!(direction, -0.5);
+
+        // Straight ahead is the long way round, behind is the short one
+        let port = Port::new(na::Point2::new(0.5, 0.1));
+        let position = na::Point2::new(0.5, 0.9);
+
+        let (direction, distance) = port.bearing(position, na::Rotation2::new(0.0), &boundary);
+        assert_relative_eq!(direction, 0.0);
+        assert_relative_eq!(distance, 0.2, epsilon = 1e-6);
+
+        let boundary = BoundaryConfig::Reflective;
+
+        let (direction, distance) = port.bearing(position, na::Rotation2::new(0.0), &boundary);
+        assert_relative_eq!(direction.abs(), 1.0);
+        assert_relative_eq!(distance, 0.8, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_nearest() {
+        let ports = [
+            Port::new(na::Point2::new(0.1, 0.1)),
+            Port::new(na::Point2::new(0.8, 0.8)),
+        ];
+
+        let boundary = BoundaryConfig::Reflective;
+
+        let nearest = Port::nearest(&ports, na::Point2::new(0.6, 0.7), &boundary).unwrap();
+        assert_eq!(nearest.position(), na::Point2::new(0.8, 0.8));
+        assert!(Port::nearest(&[], na::Point2::new(0.6, 0.7), &boundary).is_none());
+
+        // Across the edges, the other port is closer
+        let position = na::Point2::new(0.98, 0.98);
+
+        let nearest = Port::nearest(&ports, position, &BoundaryConfig::Toroidal).unwrap();
+        assert_eq!(nearest.position(), na::Point2::new(0.1, 0.1));
+
+        let nearest = Port::nearest(&ports, position, &boundary).unwrap();
+        assert_eq!(nearest.position(), na::Point2::new(0.8, 0.8));
+    }
+
+    #[test]
+    fn test_reaches() {
+        let port = Port::new(na::Point2::new(0.02, 0.5));
+        let position = na::Point2::new(0.99, 0.5);
+
+        let mut config = SimulationConfig::default();
+        config.ports.radius = 0.05;
+
+        config.boundary = BoundaryConfig::Toroidal;
+        assert!(port.reaches(position, &config));
+
+        config.boundary = BoundaryConfig::Reflective;
+        assert!(!port.reaches(position, &config));
+    }
+}
@@ -0,0 +1,321 @@
+use crate::*;
+
+/// Casts rays evenly spread over its field of view and reports, for each of
+/// its channels, how far along each ray the nearest entity of the channel's
+/// class lies.
+///
+/// Wastes, collectors and ports are seen as discs; land stops every ray, so
+/// nothing behind it shows up.
+#[derive(Debug)]
+pub struct RayEye {
+    fov_range: f32,
+    fov_angle: f32,
+    rays: usize,
+    channels: Vec<RayChannelConfig>,
+    /// Size of the wastes of each category (their collection radius)
+    waste_radii: Vec<f32>,
+    /// How far away the wastes of each category can be seen
+    waste_ranges: Vec<f32>,
+    collector_radius: f32,
+    port_radius: f32,
+    boundary: BoundaryConfig,
+}
+
+impl RayEye {
+    pub fn from_config(
+        config: &SimulationConfig,
+        channels: &[RayChannelConfig],
+        collector_radius: f32,
+    ) -> Self {
+        assert!(config.eye.fov_range > 0.0);
+        assert!(config.eye.fov_angle > 0.0);
+        assert!(config.eye.cells > 0);
+        assert!(!channels.is_empty());
+
+        Self {
+            fov_range: config.eye.fov_range,
+            fov_angle: config.eye.fov_angle,
+            rays: config.eye.cells,
+            channels: channels.to_vec(),
+            waste_radii: config
+                .wastes
+                .categories
+                .iter()
+                .map(|category| category.radius(config))
+                .collect(),
+            waste_ranges: config
+                .wastes
+                .categories
+                .iter()
+                .map(|category| category.visibility * config.eye.fov_range)
+                .collect(),
+            collector_radius,
+            port_radius: config.ports.radius,
+            boundary: config.boundary,
+        }
+    }
+
+    /// Direction of each ray, from one side of the field of view to the
+    /// other (in the same order as `CellEye`'s cells)
+    fn directions(
+        &self,
+        rotation: na::Rotation2<f32>,
+    ) -> impl Iterator<Item = na::Vector2<f32>> + '_ {
+        (0..self.rays).map(move |ray| {
+            let share = (ray as f32 + 0.5) / (self.rays as f32);
+            let angle = -self.fov_angle / 2.0 + self.fov_angle * share;
+
+            math::rotation(math::angle(rotation) + angle) * na::Vector2::y()
+        })
+    }
+
+    /// Distance along the ray to the disc of `radius` around `target`, if
+    /// it is hit within range
+    fn cast(
+        &self,
+        position: na::Point2<f32>,
+        direction: na::Vector2<f32>,
+        target: na::Point2<f32>,
+        radius: f32,
+    ) -> Option<f32> {
+        let offset = self.boundary.offset(position, target);
+
+        if offset.norm() <= radius {
+            return Some(0.0);
+        }
+
+        let along = offset.dot(&direction);
+        let across = offset.norm_squared() - along * along;
+
+        if along <= 0.0 || across > radius * radius {
+            return None;
+        }
+
+        let distance = along - (radius * radius - across).sqrt();
+        (distance <= self.fov_range).then_some(distance)
+    }
+}
+
+impl Eye for RayEye {
+    /// The rays of the first channel, followed by the rays of the second
+    /// one, and so on
+    fn outputs(&self) -> usize {
+        self.rays * self.channels.len()
+    }
+
+    fn process_vision(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        surroundings: &Surroundings,
+    ) -> Vec<f32> {
+        let mut outputs = vec![0.0; self.outputs()];
+
+        let reach = self.fov_range + self.waste_radii.iter().copied().fold(0.0, f32::max);
+        let wastes: Vec<_> = surroundings.wastes_near(position, reach).collect();
+
+        for (ray, direction) in self.directions(rotation).enumerate() {
+            let end = position + direction * self.fov_range;
+
+            let hit_obstacle = |land_only: bool| {
+                let obstacles = surroundings
+                    .obstacles
+                    .iter()
+                    .filter(|obstacle| !land_only || obstacle.is_land());
+
+                obstacle::first_crossing(obstacles, position, end, &self.boundary)
+                    .map(|(t, _)| t * self.fov_range)
+            };
+
+            let land = hit_obstacle(true).unwrap_or(f32::INFINITY);
+
+            for (channel, config) in self.channels.iter().enumerate() {
+                let hit = match config.class {
+                    EntityClass::Waste => wastes
+                        .iter()
+                        .filter_map(|waste| {
+                            let radius = self.waste_radii[waste.category];
+                            let range = self.waste_ranges[waste.category];
+
+                            self.cast(position, direction, waste.position, radius)
+                                .filter(|&distance| distance <= range)
+                        })
+                        .reduce(f32::min),
+
+                    EntityClass::Collector => surroundings
+                        .others()
+                        .filter_map(|other| {
+                            self.cast(position, direction, other.position, self.collector_radius)
+                        })
+                        .reduce(f32::min),
+
+                    EntityClass::Port => surroundings
+                        .ports
+                        .iter()
+                        .filter_map(|port| {
+                            self.cast(position, direction, port.position, self.port_radius)
+                        })
+                        .reduce(f32::min),
+
+                    EntityClass::Obstacle => hit_obstacle(false),
+                };
+
+                let hit = match config.class {
+                    EntityClass::Obstacle => hit,
+                    _ => hit.filter(|&distance| distance < land),
+                };
+
+                outputs[channel * self.rays + ray] =
+                    config.normalization.normalize(hit, self.fov_range);
+            }
+        }
+
+        outputs
+    }
+}
+
+impl NormalizationConfig {
+    /// Turns the distance to the nearest hit (`None` if nothing was hit)
+    /// into a channel's value
+    pub fn normalize(&self, hit: Option<f32>, range: f32) -> f32 {
+        match self {
+            Self::Proximity => hit.map_or(0.0, |distance| 1.0 - distance / range),
+            Self::Distance => hit.map_or(1.0, |distance| distance / range),
+            Self::Raw => hit.unwrap_or(range),
+            Self::Exponential { scale } => {
+                hit.map_or(0.0, |distance| math::exp(-distance / scale))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn eye(channels: Vec<RayChannelConfig>) -> RayEye {
+        let mut config = SimulationConfig::default();
+        config.eye.cells = 3;
+        config.collection_radius = 0.01;
+
+        RayEye::from_config(&config, &channels, 0.01)
+    }
+
+    #[test]
+    fn test_nearest_hit() {
+        let eye = eye(vec![RayChannelConfig {
+            class: EntityClass::Waste,
+            normalization: NormalizationConfig::Raw,
+        }]);
+
+        // Both right ahead, on the middle ray
+        let wastes = [
+            Waste::new(na::Point2::new(0.5, 0.7), 0),
+            Waste::new(na::Point2::new(0.5, 0.6), 0),
+        ];
+
+        let surroundings = Surroundings {
+            wastes: &wastes,
+            ..Default::default()
+        };
+
+        let vision = eye.process_vision(
+            na::Point2::new(0.5, 0.5),
+            na::Rotation2::new(0.0),
+            &surroundings,
+        );
+
+        assert_eq!(vision.len(), 3);
+        assert_relative_eq!(vision[0], 0.25);
+        assert_relative_eq!(vision[1], 0.09, epsilon = 1e-5);
+        assert_relative_eq!(vision[2], 0.25);
+    }
+
+    #[test]
+    fn test_channels() {
+        let eye = eye(vec![
+            RayChannelConfig {
+                class: EntityClass::Waste,
+                normalization: NormalizationConfig::Proximity,
+            },
+            RayChannelConfig {
+                class: EntityClass::Obstacle,
+                normalization: NormalizationConfig::Distance,
+            },
+        ]);
+
+        let wastes = [Waste::new(na::Point2::new(0.5, 0.7), 0)];
+
+        // Land between the collector and the waste
+        let obstacles = [Obstacle::from_config(&ObstacleConfig {
+            kind: ObstacleKind::Island,
+            points: vec![[0.4, 0.6], [0.6, 0.6], [0.6, 0.65], [0.4, 0.65]],
+        })];
+
+        let vision = |obstacles: &[Obstacle]| {
+            let surroundings = Surroundings {
+                wastes: &wastes,
+                obstacles,
+                ..Default::default()
+            };
+
+            eye.process_vision(
+                na::Point2::new(0.5, 0.5),
+                na::Rotation2::new(0.0),
+                &surroundings,
+            )
+        };
+
+        let clear = vision(&[]);
+        assert_relative_eq!(clear[1], 1.0 - 0.19 / 0.25, epsilon = 1e-5);
+        assert_eq!(clear[4], 1.0);
+
+        let hidden = vision(&obstacles);
+        assert_eq!(hidden[1], 0.0);
+        assert_relative_eq!(hidden[4], 0.1 / 0.25, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_visibility() {
+        let mut config = SimulationConfig::default();
+        config.eye.cells = 3;
+        config.collection_radius = 0.01;
+        config.wastes.categories[0].visibility = 0.5;
+
+        let channels = [RayChannelConfig {
+            class: EntityClass::Waste,
+            normalization: NormalizationConfig::Raw,
+        }];
+
+        let eye = RayEye::from_config(&config, &channels, 0.01);
+
+        let vision = |y| {
+            let wastes = [Waste::new(na::Point2::new(0.5, y), 0)];
+
+            let surroundings = Surroundings {
+                wastes: &wastes,
+                ..Default::default()
+            };
+
+            eye.process_vision(na::Point2::new(0.5, 0.5), na::Rotation2::new(0.0), &surroundings)
+        };
+
+        // Half as visible, so seen only half as far
+        assert_relative_eq!(vision(0.6)[1], 0.09, epsilon = 1e-5);
+        assert_eq!(vision(0.7)[1], 0.25);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let range = 0.5;
+
+        assert_eq!(NormalizationConfig::Proximity.normalize(Some(0.25), range), 0.5);
+        assert_eq!(NormalizationConfig::Proximity.normalize(None, range), 0.0);
+        assert_eq!(NormalizationConfig::Distance.normalize(None, range), 1.0);
+        assert_eq!(NormalizationConfig::Raw.normalize(None, range), 0.5);
+
+        let exponential = NormalizationConfig::Exponential { scale: 0.1 };
+        assert_relative_eq!(exponential.normalize(Some(0.1), range), (-1.0f32).exp());
+    }
+}
@@ -4,19 +4,68 @@ use crate::*;
 pub struct Sea{
     pub(crate) collectors: Vec<Collector>,
     pub(crate) wastes: Vec<Waste>,
+    pub(crate) waste_index: SpatialIndex,
+    pub(crate) ports: Vec<Port>,
+    pub(crate) currents: Currents,
+    pub(crate) spawner: Spawner,
+    pub(crate) obstacles: Vec<Obstacle>,
+    /// Steps since the start of the episode
+    pub(crate) age: usize,
 }
 
 impl Sea {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let collectors = (0..40)
-            .map(|_| Collector::random(rng))
+    pub fn random(config: &SimulationConfig, rng: &mut dyn RngCore) -> Self {
+        let collectors = (0..config.population_size)
+            .map(|_| Collector::random(config, rng))
             .collect();
 
-        let wastes = (0..60)
-            .map(|_| Waste::random(rng))
+        Self::with_collectors(collectors, config, rng)
+    }
+
+    /// Spawns fresh wastes around already existing collectors
+    pub(crate) fn with_collectors(
+        collectors: Vec<Collector>,
+        config: &SimulationConfig,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let spawner = Spawner::from_config(&config.wastes);
+
+        let wastes: Vec<_> = (0..config.waste_count)
+            .map(|_| spawner.spawn(rng))
+            .collect();
+
+        let waste_index = SpatialIndex::new(
+            &config.spatial_index,
+            wastes.iter().map(Waste::position),
+        )
+        .with_boundary(&config.boundary);
+
+        let ports = config
+            .ports
+            .positions
+            .iter()
+            .map(|&[x, y]| Port::new(na::Point2::new(x, y)))
+            .collect();
+
+        let currents = Currents::from_config(&config.currents);
+
+        let obstacles = config
+            .obstacles
+            .polygons
+            .iter()
+            .map(Obstacle::from_config)
             .collect();
 
-        Self { collectors, wastes}
+        Self {
+            collectors,
+            wastes,
+            waste_index,
+            ports,
+            currents,
+            spawner,
+            obstacles,
+            age: 0,
+        }
     }
 
     pub fn collectors(&self) -> &[Collector] {
@@ -26,4 +75,427 @@ impl Sea {
     pub fn wastes(&self) -> &[Waste] {
         &self.wastes
     }
+
+    pub fn ports(&self) -> &[Port] {
+        &self.ports
+    }
+
+    pub fn currents(&self) -> &Currents {
+        &self.currents
+    }
+
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+
+    /// Lays out a fresh set of wastes, for a new episode
+    pub(crate) fn reset_wastes(&mut self, config: &SimulationConfig, rng: &mut dyn RngCore) {
+        let spawner = &self.spawner;
+
+        self.wastes = (0..config.waste_count)
+            .map(|_| spawner.spawn(rng))
+            .collect();
+
+        self.spawner.reset();
+        self.age = 0;
+    }
+
+    /// Re-indexes every waste, after they have been moved around
+    pub(crate) fn reindex_wastes(&mut self) {
+        self.waste_index
+            .rebuild(self.wastes.iter().map(Waste::position));
+    }
+
+    pub(crate) fn step(&mut self, config: &SimulationConfig, rng: &mut dyn RngCore) {
+        self.process_obstacles(rng);
+        self.process_collisions(config, rng);
+        self.process_brains(config, rng);
+        self.process_movements(config);
+        self.process_separation(config);
+        self.process_currents(config);
+        self.process_ports(config);
+
+        self.age += 1;
+    }
+
+    /// Moves whatever has been (re)spawned inside an obstacle out of it:
+    /// collectors to a random spot, wastes to wherever they could spawn
+    fn process_obstacles(&mut self, rng: &mut dyn RngCore) {
+        if self.obstacles.is_empty() {
+            return;
+        }
+
+        for collector in &mut self.collectors {
+            obstacle::relocate(&self.obstacles, &mut collector.position, || rng.gen());
+        }
+
+        let land = self.obstacles.iter().filter(|obstacle| obstacle.is_land());
+
+        for waste in &mut self.wastes {
+            let spawner = &self.spawner;
+            obstacle::relocate(land.clone(), &mut waste.position, || spawner.sample(rng));
+        }
+    }
+
+    fn process_movements(&mut self, config: &SimulationConfig) {
+        for collector in &mut self.collectors {
+            collector.metrics.steps += 1;
+
+            if !collector.is_stranded(config) {
+                let energy_per_distance = config.collector.energy_per_distance;
+                let mut distance = if config.physics.enabled {
+                    config.physics.distance(collector.speed)
+                } else {
+                    collector.speed
+                };
+
+                // The last drop of fuel only carries the collector so far
+                if config.fuel.enabled && energy_per_distance > 0.0 {
+                    distance = distance.min(collector.fuel / energy_per_distance);
+                }
+
+                let start = collector.position;
+                let delta = collector.rotation * na::Vector2::new(0.0, distance);
+
+                match obstacle::travel(
+                    &self.obstacles,
+                    collector.position,
+                    delta,
+                    config.obstacles.collision,
+                    &config.boundary,
+                ) {
+                    Some(position) => {
+                        distance = na::distance(&collector.position, &position);
+                        collector.position = position;
+                        collector.metrics.collisions += 1;
+                    }
+                    None => collector.position += delta,
+                }
+
+                let edges = config.boundary.confine(&mut collector.position);
+
+                if edges.any() {
+                    match config.boundary {
+                        BoundaryConfig::Reflective => {
+                            collector.rotation = boundary::bounce(collector.rotation, edges);
+                        }
+                        BoundaryConfig::Wall { .. } => {
+                            distance = na::distance(&start, &collector.position);
+                            collector.metrics.wall_hits += 1;
+                        }
+                        BoundaryConfig::Toroidal => (),
+                    }
+                }
+
+                let energy = distance * energy_per_distance;
+
+                collector.metrics.distance += distance;
+                collector.metrics.energy += energy;
+                collector.burn(energy, config);
+            }
+        }
+    }
+
+    /// Pushes apart the collectors that came too close to each other, each
+    /// of them counting a collision
+    fn process_separation(&mut self, config: &SimulationConfig) {
+        if !config.separation.enabled {
+            return;
+        }
+
+        let radius = config.separation.radius;
+
+        for a in 0..self.collectors.len() {
+            for b in (a + 1)..self.collectors.len() {
+                let offset = config
+                    .boundary
+                    .offset(self.collectors[a].position, self.collectors[b].position);
+
+                let distance = offset.norm();
+
+                if distance >= radius {
+                    continue;
+                }
+
+                let direction = if distance > 0.0 {
+                    offset / distance
+                } else {
+                    na::Vector2::x()
+                };
+
+                let push = direction * (radius - distance) / 2.0;
+
+                for (id, push) in [(a, -push), (b, push)] {
+                    let collector = &mut self.collectors[id];
+
+                    collector.position = obstacle::travel(
+                        &self.obstacles,
+                        collector.position,
+                        push,
+                        CollisionConfig::Stop,
+                        &config.boundary,
+                    )
+                    .unwrap_or(collector.position + push);
+
+                    config.boundary.confine(&mut collector.position);
+                    collector.metrics.collisions += 1;
+                }
+            }
+        }
+    }
+
+    /// Drifts wastes and collectors along the currents; this is free, and
+    /// not counted as distance travelled. Whatever would drift into an
+    /// obstacle (only land, for wastes) stays put.
+    fn process_currents(&mut self, config: &SimulationConfig) {
+        if self.currents.is_still() {
+            return;
+        }
+
+        let land: Vec<_> = self
+            .obstacles
+            .iter()
+            .filter(|obstacle| obstacle.is_land())
+            .collect();
+
+        let all: Vec<_> = self.obstacles.iter().collect();
+
+        for collector in &mut self.collectors {
+            drift(&self.currents, self.age, &all, &config.boundary, &mut collector.position);
+        }
+
+        for waste in &mut self.wastes {
+            drift(&self.currents, self.age, &land, &config.boundary, &mut waste.position);
+        }
+    }
+
+    /// Refuels and unloads the collectors docked at a port
+    fn process_ports(&mut self, config: &SimulationConfig) {
+        if !config.fuel.enabled && !config.cargo.enabled {
+            return;
+        }
+
+        for collector in &mut self.collectors {
+            let docked = self
+                .ports
+                .iter()
+                .any(|port| port.reaches(collector.position, config));
+
+            if config.fuel.enabled {
+                if docked {
+                    collector.fuel = (collector.fuel + config.fuel.refuel_rate)
+                        .min(config.fuel.capacity);
+                }
+
+                collector.metrics.fuel = Some(collector.fuel);
+            }
+
+            if config.cargo.enabled {
+                if docked {
+                    collector.metrics.wastes_delivered += collector.cargo_wastes;
+                    collector.metrics.value_delivered += collector.cargo_value;
+                    collector.cargo = 0;
+                    collector.cargo_wastes = 0;
+                    collector.cargo_value = 0.0;
+                }
+
+                collector.metrics.cargo = Some(collector.cargo);
+            }
+        }
+    }
+
+    fn process_collisions(&mut self, config: &SimulationConfig, rng: &mut dyn RngCore) {
+        let categories = &config.wastes.categories;
+
+        // Wide enough for every category
+        let reach = categories
+            .iter()
+            .map(|category| category.radius(config))
+            .fold(0.0, f32::max);
+
+        // Wastes are moved around by evolution and replays as well, so
+        // the index is simply rebuilt once per step
+        self.reindex_wastes();
+
+        // Wastes that will not respawn are only dropped once every collector
+        // is done, so that ids stay valid in the meantime
+        let mut gone = vec![false; self.wastes.len()];
+
+        for collector in &mut self.collectors {
+            if collector.is_full(config) {
+                continue;
+            }
+
+            // Candidates come sorted, so wastes respawn in the same order
+            // (and consume the RNG the same way) as with a brute-force loop
+            let candidates = self.waste_index.query(collector.position, reach);
+
+            for id in candidates {
+                if gone[id] {
+                    continue;
+                }
+
+                let waste = &mut self.wastes[id];
+                let category = &categories[waste.category];
+
+                let distance = config.boundary.distance(
+                    collector.position,
+                    waste.position,
+                );
+
+                // A collector filling up leaves the remaining wastes (or
+                // the ones too heavy for it) be
+                if distance <= category.radius(config)
+                    && collector.can_carry(category.mass, config)
+                {
+                    if config.cargo.enabled {
+                        collector.cargo += category.mass;
+                        collector.cargo_wastes += 1;
+                        collector.cargo_value += category.value;
+                    }
+
+                    let metrics = &mut collector.metrics;
+                    metrics.wastes_collected += 1;
+                    metrics.value_collected += category.value;
+                    metrics.first_pickup.get_or_insert(metrics.steps);
+
+                    if self.spawner.collected(waste, rng) {
+                        self.waste_index.update(id, waste.position);
+                    } else {
+                        gone[id] = true;
+                    }
+                }
+            }
+        }
+
+        let mut removed = false;
+
+        if gone.contains(&true) {
+            let mut gone = gone.into_iter();
+            self.wastes.retain(|_| !gone.next().unwrap());
+            removed = true;
+        }
+
+        // Brains look at the wastes through the index later in this step
+        if self.spawner.emit(&mut self.wastes, rng) || removed {
+            self.reindex_wastes();
+        }
+    }
+
+    fn process_brains(&mut self, config: &SimulationConfig, rng: &mut dyn RngCore) {
+        // Every collector makes up its mind before any of them turns, so
+        // that the ones seeing each other do not depend on their order
+        let responses: Vec<_> = self
+            .collectors
+            .iter()
+            .enumerate()
+            .map(|(id, collector)| {
+                if collector.is_stranded(config) {
+                    return None;
+                }
+
+                let inputs = self.inputs(id, config, rng);
+                Some(collector.brain.nn.propagate(inputs))
+            })
+            .collect();
+
+        for (collector, response) in self.collectors.iter_mut().zip(responses) {
+            let Some(response) = response else {
+                continue;
+            };
+
+            let limits = &config.collector;
+
+            let rotation = if config.physics.enabled {
+                config.physics.steer(collector, &response)
+            } else {
+                let speed = response[0].clamp(
+                    -limits.speed_accel,
+                    limits.speed_accel,
+                );
+
+                let rotation = response[1].clamp(
+                    -limits.rotation_accel,
+                    limits.rotation_accel,
+                );
+
+                collector.speed = (collector.speed + speed)
+                    .clamp(limits.speed_min, limits.speed_max);
+
+                collector.rotation = math::rotation(math::angle(collector.rotation) + rotation);
+
+                rotation
+            };
+
+            let energy = rotation.abs() * limits.energy_per_radian;
+
+            collector.metrics.energy += energy;
+            collector.burn(energy, config);
+        }
+    }
+
+    /// What the brain of the `id`-th collector is fed, see `Brain::inputs()`
+    fn inputs(&self, id: usize, config: &SimulationConfig, rng: &mut dyn RngCore) -> Vec<f32> {
+        let collector = &self.collectors[id];
+        let noise = &config.eye.noise;
+
+        let surroundings = Surroundings {
+            wastes: &self.wastes,
+            waste_index: Some(&self.waste_index),
+            obstacles: &self.obstacles,
+            ports: &self.ports,
+            collectors: &self.collectors,
+            viewer: Some(id),
+        };
+
+        let mut inputs = if noise.is_exact() {
+            collector.eye.process_vision(collector.position, collector.rotation, &surroundings)
+        } else {
+            let detected;
+
+            let surroundings = if noise.hides_wastes() {
+                detected = noise.detect(collector.position, &surroundings, config, rng);
+
+                Surroundings {
+                    wastes: &detected,
+                    waste_index: None,
+                    ..surroundings
+                }
+            } else {
+                surroundings
+            };
+
+            let mut vision = collector.eye.process_vision(
+                collector.position,
+                collector.rotation,
+                &surroundings,
+            );
+
+            noise.apply(&mut vision, rng);
+            vision
+        };
+
+        inputs.extend(senses::sense(collector, &self.ports, self.age, config));
+        inputs
+    }
+}
+
+fn drift(
+    currents: &Currents,
+    time: usize,
+    obstacles: &[&Obstacle],
+    boundary: &BoundaryConfig,
+    position: &mut na::Point2<f32>,
+) {
+    let target = *position + currents.velocity(*position, time, boundary);
+
+    let crossing =
+        obstacle::first_crossing(obstacles.iter().copied(), *position, target, boundary);
+
+    if crossing.is_some() {
+        return;
+    }
+
+    *position = target;
+    boundary.confine(position);
 }
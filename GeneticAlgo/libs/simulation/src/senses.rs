@@ -0,0 +1,182 @@
+use crate::*;
+
+impl SensesConfig {
+    /// Whether the direction of and distance to the nearest port are fed;
+    /// unless set explicitly, they are with a cargo model only
+    pub fn port_enabled(&self, config: &SimulationConfig) -> bool {
+        self.port.unwrap_or(config.cargo.enabled)
+    }
+
+    /// Number of values returned by `sense()`
+    pub(crate) fn inputs(&self, config: &SimulationConfig) -> usize {
+        let mut inputs = 0;
+
+        if self.fuel && config.fuel.enabled {
+            inputs += 1;
+        }
+
+        if self.cargo && config.cargo.enabled {
+            inputs += 1;
+        }
+
+        if self.port_enabled(config) {
+            inputs += 2;
+        }
+
+        if self.speed {
+            inputs += 1;
+        }
+
+        if self.heading {
+            inputs += 2;
+        }
+
+        if self.position {
+            inputs += 2;
+        }
+
+        if self.time {
+            inputs += 1;
+        }
+
+        inputs
+    }
+}
+
+/// What a collector knows about itself, fed to its brain after what its eye
+/// sees: remaining fuel, load, nearest port, speed, heading, position and
+/// share of the generation left - each only if enabled in `senses`
+pub(crate) fn sense(
+    collector: &Collector,
+    ports: &[Port],
+    age: usize,
+    config: &SimulationConfig,
+) -> Vec<f32> {
+    let senses = &config.senses;
+    let mut inputs = Vec::with_capacity(senses.inputs(config));
+
+    if senses.fuel && config.fuel.enabled {
+        inputs.push(collector.fuel / config.fuel.capacity);
+    }
+
+    if senses.cargo && config.cargo.enabled {
+        inputs.push(collector.cargo as f32 / config.cargo.capacity as f32);
+    }
+
+    if senses.port_enabled(config) {
+        let (direction, distance) = Port::nearest(ports, collector.position, &config.boundary)
+            .map(|port| port.bearing(collector.position, collector.rotation, &config.boundary))
+            .unwrap_or((0.0, 0.0));
+
+        inputs.push(direction);
+        inputs.push(distance);
+    }
+
+    if senses.speed {
+        // The physics model is not bound by the collector's speed limits
+        let (speed_min, speed_max) = if config.physics.enabled {
+            (0.0, config.physics.top_speed())
+        } else {
+            (config.collector.speed_min, config.collector.speed_max)
+        };
+
+        let speed_range = speed_max - speed_min;
+
+        inputs.push(if speed_range > 0.0 && speed_range.is_finite() {
+            (collector.speed - speed_min) / speed_range
+        } else {
+            0.0
+        });
+    }
+
+    if senses.heading {
+        let (sin, cos) = math::sin_cos(math::angle(collector.rotation));
+
+        inputs.push(sin);
+        inputs.push(cos);
+    }
+
+    if senses.position {
+        inputs.push(collector.position.x);
+        inputs.push(collector.position.y);
+    }
+
+    if senses.time {
+        let steps = (config.generation_length + 1) as f32;
+        inputs.push((1.0 - age as f32 / steps).max(0.0));
+    }
+
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_inputs() {
+        let mut config = SimulationConfig::default();
+        assert_eq!(config.senses.inputs(&config), 0);
+
+        config.cargo.enabled = true;
+        assert_eq!(config.senses.inputs(&config), 3);
+
+        config.senses.port = Some(false);
+        config.senses.heading = true;
+        config.senses.time = true;
+        assert_eq!(config.senses.inputs(&config), 4);
+    }
+
+    #[test]
+    fn test_sense() {
+        let mut config = SimulationConfig {
+            generation_length: 99,
+            ..Default::default()
+        };
+
+        config.senses = SensesConfig {
+            speed: true,
+            heading: true,
+            position: true,
+            port: Some(true),
+            time: true,
+            ..Default::default()
+        };
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut collector = Collector::random(&config, &mut rng);
+        collector.position = na::Point2::new(0.25, 0.5);
+        collector.rotation = na::Rotation2::new(0.0);
+        collector.speed = config.collector.speed_max;
+
+        let ports = [Port::new(na::Point2::new(0.25, 0.75))];
+        let inputs = sense(&collector, &ports, 25, &config);
+
+        assert_eq!(inputs.len(), config.senses.inputs(&config));
+        assert_relative_eq!(inputs[0], 0.0);
+        assert_relative_eq!(inputs[1], 0.25);
+        assert_relative_eq!(inputs[2], 1.0);
+        assert_relative_eq!(inputs[3], 0.0);
+        assert_relative_eq!(inputs[4], 1.0);
+        assert_eq!(&inputs[5..7], &[0.25, 0.5]);
+        assert_relative_eq!(inputs[7], 0.75);
+    }
+
+    #[test]
+    fn test_sense_physics() {
+        let mut config = SimulationConfig::default();
+        config.senses.speed = true;
+        config.physics.enabled = true;
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut collector = Collector::random(&config, &mut rng);
+
+        collector.speed = config.physics.top_speed() / 2.0;
+        assert_relative_eq!(sense(&collector, &[], 0, &config)[0], 0.5);
+
+        // Without drag there is no top speed to compare with
+        config.physics.linear_drag = 0.0;
+        assert_relative_eq!(sense(&collector, &[], 0, &config)[0], 0.0);
+    }
+}
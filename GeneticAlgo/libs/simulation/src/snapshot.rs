@@ -0,0 +1,304 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Version of the on-disk format; bumped on every incompatible change
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Prefix of the binary format, followed by the version (little-endian)
+/// and a bincode payload
+const BINARY_MAGIC: &[u8; 4] = b"MWCS";
+
+/// A single trained brain, along with where it comes from
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BrainSnapshot {
+    pub topology: Vec<nn::LayerTopology>,
+    pub activation: nn::Activation,
+    pub weights: Vec<f32>,
+
+    /// Self-adaptive mutation step sizes; empty for other mutation methods
+    #[serde(default)]
+    pub step_sizes: Vec<f32>,
+
+    /// Generation this brain was born in (or evaluated during, for hall of
+    /// fame entries)
+    pub generation: usize,
+
+    /// `None` when the brain has not been evaluated yet
+    pub fitness: Option<f32>,
+
+    /// `SimulationConfig::hash()` of the simulation that trained it
+    pub config_hash: u64,
+}
+
+/// A whole simulation's population, enough to resume training
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PopulationSnapshot {
+    pub config: SimulationConfig,
+    pub config_hash: u64,
+
+    /// Number of generations evolved before the snapshot was taken
+    pub generation: usize,
+
+    pub brains: Vec<BrainSnapshot>,
+
+    #[serde(default)]
+    pub hall_of_fame: Vec<BrainSnapshot>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    version: u32,
+    #[serde(flatten)]
+    data: T,
+}
+
+/// Bincode cannot decode the internally tagged enums of `SimulationConfig`,
+/// so the binary format embeds the config as JSON
+#[derive(Serialize, Deserialize)]
+struct BinaryPopulation {
+    config: String,
+    config_hash: u64,
+    generation: usize,
+    brains: Vec<BrainSnapshot>,
+    hall_of_fame: Vec<BrainSnapshot>,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    UnsupportedVersion(u32),
+    InvalidTopology {
+        expected: Vec<nn::LayerTopology>,
+        actual: Vec<nn::LayerTopology>,
+    },
+    InvalidWeights {
+        expected: usize,
+        actual: usize,
+    },
+    EmptyPopulation,
+    Config(ConfigError),
+    Json(serde_json::Error),
+    Binary(String),
+}
+
+impl BrainSnapshot {
+    pub(crate) fn new(
+        brain: &Brain,
+        generation: usize,
+        fitness: Option<f32>,
+        config_hash: u64,
+    ) -> Self {
+        Self {
+            topology: brain.nn.topology(),
+            activation: brain.nn.activation(),
+            weights: brain.nn.weights().collect(),
+            step_sizes: brain.step_sizes.clone(),
+            generation,
+            fitness,
+            config_hash,
+        }
+    }
+
+    pub(crate) fn from_hall_of_fame(
+        entry: &ga::HallOfFameEntry,
+        config: &SimulationConfig,
+        config_hash: u64,
+    ) -> Self {
+        Self {
+            topology: Brain::topology(config).to_vec(),
+            activation: nn::Activation::Relu,
+            weights: entry.chromosome().iter().copied().collect(),
+            step_sizes: entry.chromosome().step_sizes().to_vec(),
+            generation: entry.generation(),
+            fitness: Some(entry.fitness()),
+            config_hash,
+        }
+    }
+
+    /// Checks that this brain fits collectors built from `config`
+    pub(crate) fn to_chromosome(
+        &self,
+        config: &SimulationConfig,
+    ) -> Result<ga::Chromosome, SnapshotError> {
+        let expected = Brain::topology(config).to_vec();
+
+        if self.topology != expected {
+            return Err(SnapshotError::InvalidTopology {
+                expected,
+                actual: self.topology.clone(),
+            });
+        }
+
+        let weights = nn::Network::neuron_layout(&self.topology)
+            .into_iter()
+            .sum();
+
+        if self.weights.len() != weights {
+            return Err(SnapshotError::InvalidWeights {
+                expected: weights,
+                actual: self.weights.len(),
+            });
+        }
+
+        if !self.step_sizes.is_empty() && self.step_sizes.len() != weights {
+            return Err(SnapshotError::InvalidWeights {
+                expected: weights,
+                actual: self.step_sizes.len(),
+            });
+        }
+
+        Ok(self
+            .weights
+            .iter()
+            .copied()
+            .collect::<ga::Chromosome>()
+            .with_step_sizes(self.step_sizes.clone()))
+    }
+
+    pub fn to_json(&self) -> Result<String, SnapshotError> {
+        to_json(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        from_json(json)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SnapshotError> {
+        to_bytes(self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        from_bytes(bytes)
+    }
+}
+
+impl PopulationSnapshot {
+    pub fn to_json(&self) -> Result<String, SnapshotError> {
+        to_json(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        from_json(json)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SnapshotError> {
+        let config = serde_json::to_string(&self.config)
+            .map_err(SnapshotError::Json)?;
+
+        to_bytes(&BinaryPopulation {
+            config,
+            config_hash: self.config_hash,
+            generation: self.generation,
+            brains: self.brains.clone(),
+            hall_of_fame: self.hall_of_fame.clone(),
+        })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let population: BinaryPopulation = from_bytes(bytes)?;
+
+        Ok(Self {
+            config: serde_json::from_str(&population.config)
+                .map_err(SnapshotError::Json)?,
+            config_hash: population.config_hash,
+            generation: population.generation,
+            brains: population.brains,
+            hall_of_fame: population.hall_of_fame,
+        })
+    }
+}
+
+fn to_json<T>(data: &T) -> Result<String, SnapshotError>
+where
+    T: Serialize,
+{
+    let versioned = Versioned {
+        version: SNAPSHOT_VERSION,
+        data,
+    };
+
+    serde_json::to_string(&versioned).map_err(SnapshotError::Json)
+}
+
+fn from_json<T>(json: &str) -> Result<T, SnapshotError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    // Check the version first, so that an older or newer file is reported
+    // as such instead of as a confusing missing field
+    #[derive(Deserialize)]
+    struct Version {
+        version: u32,
+    }
+
+    let Version { version } =
+        serde_json::from_str(json).map_err(SnapshotError::Json)?;
+
+    if version != SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+
+    let versioned: Versioned<T> =
+        serde_json::from_str(json).map_err(SnapshotError::Json)?;
+
+    Ok(versioned.data)
+}
+
+fn to_bytes<T>(data: &T) -> Result<Vec<u8>, SnapshotError>
+where
+    T: Serialize,
+{
+    let mut bytes = BINARY_MAGIC.to_vec();
+    bytes.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+
+    bincode::serialize_into(&mut bytes, data)
+        .map_err(|err| SnapshotError::Binary(err.to_string()))?;
+
+    Ok(bytes)
+}
+
+fn from_bytes<T>(bytes: &[u8]) -> Result<T, SnapshotError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    if bytes.len() < 8 || &bytes[0..4] != BINARY_MAGIC {
+        return Err(SnapshotError::Binary("not a snapshot".into()));
+    }
+
+    let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+
+    if version != SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+
+    bincode::deserialize(&bytes[8..])
+        .map_err(|err| SnapshotError::Binary(err.to_string()))
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported snapshot version {} (expected {})",
+                version, SNAPSHOT_VERSION,
+            ),
+            Self::InvalidTopology { expected, actual } => write!(
+                f,
+                "brain topology {:?} does not match the config's {:?}",
+                actual, expected,
+            ),
+            Self::InvalidWeights { expected, actual } => write!(
+                f,
+                "got {} weights, expected {}",
+                actual, expected,
+            ),
+            Self::EmptyPopulation => write!(f, "snapshot contains no brains"),
+            Self::Config(err) => write!(f, "{}", err),
+            Self::Json(err) => write!(f, "invalid JSON snapshot: {}", err),
+            Self::Binary(err) => write!(f, "invalid binary snapshot: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
@@ -0,0 +1,271 @@
+use crate::*;
+
+/// Finds which items (wastes) may lie close to a point without looking at
+/// every one of them.
+///
+/// Queries return a sorted superset of the matching items, so callers still
+/// apply their own distance check and visit items in the same order as a
+/// brute-force loop would - which keeps results bit-identical.
+#[derive(Clone, Debug)]
+pub enum SpatialIndex {
+    BruteForce { items: usize },
+    Grid(Grid),
+}
+
+/// Uniform grid over the unit torus (or square)
+#[derive(Clone, Debug)]
+pub struct Grid {
+    /// Number of cells along each side
+    cells: usize,
+    /// Whether queries wrap around the edges
+    toroidal: bool,
+    buckets: Vec<Vec<usize>>,
+    /// Bucket of each item
+    locations: Vec<usize>,
+}
+
+/// Guards against rounding differences between the grid and the callers'
+/// own distance computations
+const QUERY_PADDING: f32 = 1e-4;
+
+impl SpatialIndex {
+    pub fn new(
+        config: &SpatialIndexConfig,
+        positions: impl IntoIterator<Item = na::Point2<f32>>,
+    ) -> Self {
+        match *config {
+            SpatialIndexConfig::BruteForce => Self::BruteForce {
+                items: positions.into_iter().count(),
+            },
+            SpatialIndexConfig::Grid { cells } => {
+                let mut grid = Grid::new(cells);
+                grid.rebuild(positions);
+                Self::Grid(grid)
+            }
+        }
+    }
+
+    pub fn rebuild(&mut self, positions: impl IntoIterator<Item = na::Point2<f32>>) {
+        match self {
+            Self::BruteForce { items } => *items = positions.into_iter().count(),
+            Self::Grid(grid) => grid.rebuild(positions),
+        }
+    }
+
+    /// Keeps queries from wrapping around the edges of a bounded sea
+    pub fn with_boundary(mut self, boundary: &BoundaryConfig) -> Self {
+        if let Self::Grid(grid) = &mut self {
+            grid.toroidal = boundary.is_toroidal();
+        }
+
+        self
+    }
+
+    /// Must be called whenever an item moves
+    pub fn update(&mut self, item: usize, position: na::Point2<f32>) {
+        if let Self::Grid(grid) = self {
+            grid.update(item, position);
+        }
+    }
+
+    /// Items which may lie within `radius` of `center`, either directly or
+    /// across the edges of the torus
+    pub fn query(&self, center: na::Point2<f32>, radius: f32) -> Vec<usize> {
+        match self {
+            Self::BruteForce { items } => (0..*items).collect(),
+            Self::Grid(grid) => grid.query(center, radius),
+        }
+    }
+}
+
+impl Grid {
+    fn new(cells: usize) -> Self {
+        assert!(cells > 0);
+
+        Self {
+            cells,
+            toroidal: true,
+            buckets: vec![Vec::new(); cells * cells],
+            locations: Vec::new(),
+        }
+    }
+
+    fn rebuild(&mut self, positions: impl IntoIterator<Item = na::Point2<f32>>) {
+        for bucket in &mut self.buckets {
+            bucket.clear();
+        }
+
+        self.locations.clear();
+
+        for (item, position) in positions.into_iter().enumerate() {
+            let bucket = self.bucket(position);
+
+            self.buckets[bucket].push(item);
+            self.locations.push(bucket);
+        }
+    }
+
+    fn update(&mut self, item: usize, position: na::Point2<f32>) {
+        let old = self.locations[item];
+        let new = self.bucket(position);
+
+        if old != new {
+            self.buckets[old].retain(|&other| other != item);
+            self.buckets[new].push(item);
+            self.locations[item] = new;
+        }
+    }
+
+    fn query(&self, center: na::Point2<f32>, radius: f32) -> Vec<usize> {
+        let radius = radius + QUERY_PADDING;
+        let xs = self.span(center.x, radius);
+        let ys = self.span(center.y, radius);
+
+        let mut items: Vec<_> = ys
+            .flat_map(|y| xs.clone().map(move |x| y * self.cells + x))
+            .flat_map(|bucket| self.buckets[bucket].iter().copied())
+            .collect();
+
+        items.sort_unstable();
+        items.dedup();
+        items
+    }
+
+    /// Cells (along one axis) covered by `[center - radius, center + radius]`,
+    /// wrapping around the torus
+    fn span(&self, center: f32, radius: f32) -> impl Iterator<Item = usize> + Clone {
+        let cells = self.cells as isize;
+        let min = ((center - radius) * (cells as f32)).floor() as isize;
+        let max = ((center + radius) * (cells as f32)).floor() as isize;
+
+        let (min, max) = if !self.toroidal {
+            (min.max(0), max.min(cells - 1))
+        } else if max - min + 1 >= cells {
+            (0, cells - 1)
+        } else {
+            (min, max)
+        };
+
+        (min..=max).map(move |cell| cell.rem_euclid(cells) as usize)
+    }
+
+    fn bucket(&self, position: na::Point2<f32>) -> usize {
+        self.cell(position.y) * self.cells + self.cell(position.x)
+    }
+
+    fn cell(&self, coord: f32) -> usize {
+        let coord = na::wrap(coord, 0.0, 1.0);
+        ((coord * (self.cells as f32)) as usize).min(self.cells - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn positions(rng: &mut dyn RngCore, count: usize) -> Vec<na::Point2<f32>> {
+        (0..count).map(|_| rng.gen()).collect()
+    }
+
+    /// Brute-force reference, looking at both the direct and the wrapped
+    /// distance
+    fn expected(
+        positions: &[na::Point2<f32>],
+        center: na::Point2<f32>,
+        radius: f32,
+    ) -> Vec<usize> {
+        positions
+            .iter()
+            .enumerate()
+            .filter(|(_, position)| {
+                let dx = (position.x - center.x).abs();
+                let dy = (position.y - center.y).abs();
+                let dx = dx.min(1.0 - dx);
+                let dy = dy.min(1.0 - dy);
+
+                (dx * dx + dy * dy).sqrt() <= radius
+            })
+            .map(|(item, _)| item)
+            .collect()
+    }
+
+    #[test]
+    fn test_query() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let positions = positions(&mut rng, 500);
+
+        let index = SpatialIndex::new(
+            &SpatialIndexConfig::Grid { cells: 8 },
+            positions.iter().copied(),
+        );
+
+        for _ in 0..200 {
+            let center = rng.gen();
+            let radius = rng.gen_range(0.0..0.3);
+            let actual = index.query(center, radius);
+
+            assert!(actual.windows(2).all(|items| items[0] < items[1]));
+
+            for item in expected(&positions, center, radius) {
+                assert!(actual.contains(&item));
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_across_edges() {
+        let positions = [
+            na::Point2::new(0.01, 0.5),
+            na::Point2::new(0.99, 0.5),
+            na::Point2::new(0.5, 0.5),
+            na::Point2::new(0.99, 0.99),
+        ];
+
+        let index = SpatialIndex::new(
+            &SpatialIndexConfig::Grid { cells: 10 },
+            positions.iter().copied(),
+        );
+
+        assert_eq!(index.query(na::Point2::new(0.0, 0.5), 0.05), vec![0, 1]);
+        assert_eq!(index.query(na::Point2::new(0.0, 0.0), 0.05), vec![3]);
+    }
+
+    #[test]
+    fn test_query_bounded() {
+        let positions = [na::Point2::new(0.01, 0.5), na::Point2::new(0.99, 0.5)];
+
+        let index = SpatialIndex::new(
+            &SpatialIndexConfig::Grid { cells: 10 },
+            positions.iter().copied(),
+        )
+        .with_boundary(&BoundaryConfig::Reflective);
+
+        assert_eq!(index.query(na::Point2::new(0.0, 0.5), 0.05), vec![0]);
+    }
+
+    #[test]
+    fn test_update() {
+        let positions = [na::Point2::new(0.1, 0.1), na::Point2::new(0.9, 0.9)];
+
+        let mut index = SpatialIndex::new(
+            &SpatialIndexConfig::Grid { cells: 10 },
+            positions.iter().copied(),
+        );
+
+        index.update(0, na::Point2::new(0.85, 0.85));
+
+        assert_eq!(index.query(na::Point2::new(0.1, 0.1), 0.02), Vec::<usize>::new());
+        assert_eq!(index.query(na::Point2::new(0.9, 0.9), 0.1), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let index = SpatialIndex::new(
+            &SpatialIndexConfig::BruteForce,
+            vec![na::Point2::new(0.1, 0.1); 3],
+        );
+
+        assert_eq!(index.query(na::Point2::new(0.9, 0.9), 0.01), vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::*;
+use rand::distributions::{Distribution as _, WeightedIndex};
+
+/// Decides where wastes appear - initially, when respawned and when emitted
+/// by rivers; built from a `WastesConfig`
+#[derive(Clone, Debug)]
+pub struct Spawner {
+    distribution: Distribution,
+    /// Only set with several categories to pick from
+    categories: Option<WeightedIndex<f32>>,
+    respawn: RespawnConfig,
+    rivers: Vec<RiverConfig>,
+    max_count: usize,
+
+    /// Collected wastes waiting to respawn, with `RespawnConfig::RateLimited`
+    pending: usize,
+
+    /// Fractional wastes owed by the respawn rate and by each river
+    respawn_budget: f32,
+    river_budgets: Vec<f32>,
+}
+
+#[derive(Clone, Debug)]
+enum Distribution {
+    Uniform,
+    Clusters {
+        clusters: Vec<ClusterConfig>,
+        weights: WeightedIndex<f32>,
+    },
+    Raster {
+        width: usize,
+        height: usize,
+        cells: WeightedIndex<f32>,
+    },
+}
+
+impl Spawner {
+    pub fn from_config(config: &WastesConfig) -> Self {
+        let distribution = match &config.distribution {
+            DistributionConfig::Uniform => Distribution::Uniform,
+
+            DistributionConfig::Clusters { clusters } => {
+                let weights = WeightedIndex::new(clusters.iter().map(|c| c.weight))
+                    .expect("clusters need positive weights");
+
+                Distribution::Clusters {
+                    clusters: clusters.clone(),
+                    weights,
+                }
+            }
+
+            DistributionConfig::Raster { width, height, density } => {
+                assert_eq!(density.len(), width * height);
+
+                let cells = WeightedIndex::new(density.iter().copied())
+                    .expect("raster needs a positive density somewhere");
+
+                Distribution::Raster {
+                    width: *width,
+                    height: *height,
+                    cells,
+                }
+            }
+        };
+
+        assert!(!config.categories.is_empty());
+
+        let categories = (config.categories.len() > 1).then(|| {
+            WeightedIndex::new(config.categories.iter().map(|c| c.share))
+                .expect("waste categories need positive shares")
+        });
+
+        Self {
+            distribution,
+            categories,
+            respawn: config.respawn.clone(),
+            rivers: config.rivers.clone(),
+            max_count: config.max_count,
+            pending: 0,
+            respawn_budget: 0.0,
+            river_budgets: vec![0.0; config.rivers.len()],
+        }
+    }
+
+    /// New waste, of a random category
+    pub fn spawn(&self, rng: &mut dyn RngCore) -> Waste {
+        let position = self.sample(rng);
+        Waste::new(position, self.category(rng))
+    }
+
+    /// Position of a new waste
+    pub fn sample(&self, rng: &mut dyn RngCore) -> na::Point2<f32> {
+        match &self.distribution {
+            Distribution::Uniform => rng.gen(),
+
+            Distribution::Clusters { clusters, weights } => {
+                let cluster = &clusters[weights.sample(rng)];
+                gaussian(cluster.x, cluster.y, cluster.std_dev, rng)
+            }
+
+            Distribution::Raster { width, height, cells } => {
+                let cell = cells.sample(rng);
+                let x = ((cell % width) as f32 + rng.gen::<f32>()) / (*width as f32);
+                let y = ((cell / width) as f32 + rng.gen::<f32>()) / (*height as f32);
+
+                na::Point2::new(x, y)
+            }
+        }
+    }
+
+    fn category(&self, rng: &mut dyn RngCore) -> usize {
+        self.categories
+            .as_ref()
+            .map_or(0, |categories| categories.sample(rng))
+    }
+
+    /// Forgets about pending respawns and river emissions, for a new episode
+    pub(crate) fn reset(&mut self) {
+        self.pending = 0;
+        self.respawn_budget = 0.0;
+
+        for budget in &mut self.river_budgets {
+            *budget = 0.0;
+        }
+    }
+
+    /// Handles a waste that has just been picked up; returns whether it
+    /// stays in the sea (moved elsewhere) or has to be removed
+    pub(crate) fn collected(&mut self, waste: &mut Waste, rng: &mut dyn RngCore) -> bool {
+        match self.respawn {
+            RespawnConfig::Immediate => {
+                *waste = self.spawn(rng);
+                true
+            }
+
+            RespawnConfig::RateLimited { .. } => {
+                self.pending += 1;
+                false
+            }
+
+            RespawnConfig::None => false,
+        }
+    }
+
+    /// Spawns the wastes due this step (respawns and river emissions);
+    /// returns whether any was spawned
+    pub(crate) fn emit(&mut self, wastes: &mut Vec<Waste>, rng: &mut dyn RngCore) -> bool {
+        let count = wastes.len();
+
+        if let RespawnConfig::RateLimited { per_step } = self.respawn {
+            if self.pending > 0 {
+                self.respawn_budget += per_step;
+            }
+
+            while self.pending > 0 && self.respawn_budget >= 1.0 {
+                wastes.push(self.spawn(rng));
+                self.pending -= 1;
+                self.respawn_budget -= 1.0;
+            }
+        }
+
+        for (id, river) in self.rivers.iter().enumerate() {
+            self.river_budgets[id] += river.rate;
+
+            while self.river_budgets[id] >= 1.0 {
+                self.river_budgets[id] -= 1.0;
+
+                if wastes.len() < self.max_count {
+                    let position = gaussian(river.x, river.y, river.spread, rng);
+                    wastes.push(Waste::new(position, self.category(rng)));
+                }
+            }
+        }
+
+        wastes.len() != count
+    }
+}
+
+/// Normally distributed position around `(x, y)`, wrapped onto the sea
+fn gaussian(x: f32, y: f32, std_dev: f32, rng: &mut dyn RngCore) -> na::Point2<f32> {
+    let dx = ga::standard_normal(rng);
+    let dy = ga::standard_normal(rng);
+
+    na::Point2::new(
+        na::wrap(x + dx * std_dev, 0.0, 1.0),
+        na::wrap(y + dy * std_dev, 0.0, 1.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rng() -> ChaCha8Rng {
+        ChaCha8Rng::from_seed(Default::default())
+    }
+
+    #[test]
+    fn test_clusters() {
+        let spawner = Spawner::from_config(&WastesConfig {
+            distribution: DistributionConfig::Clusters {
+                clusters: vec![
+                    ClusterConfig { x: 0.2, y: 0.2, std_dev: 0.02, weight: 1.0 },
+                    ClusterConfig { x: 0.8, y: 0.5, std_dev: 0.02, weight: 0.0 },
+                ],
+            },
+            ..Default::default()
+        });
+
+        let mut rng = rng();
+
+        for _ in 0..100 {
+            let position = spawner.sample(&mut rng);
+            assert!(na::distance(&position, &na::Point2::new(0.2, 0.2)) < 0.15);
+        }
+    }
+
+    #[test]
+    fn test_raster() {
+        let spawner = Spawner::from_config(&WastesConfig {
+            distribution: DistributionConfig::Raster {
+                width: 2,
+                height: 2,
+                density: vec![0.0, 0.0, 0.0, 3.0],
+            },
+            ..Default::default()
+        });
+
+        let mut rng = rng();
+
+        for _ in 0..100 {
+            let position = spawner.sample(&mut rng);
+            assert!(position.x >= 0.5 && position.y >= 0.5);
+        }
+    }
+
+    #[test]
+    fn test_rate_limited() {
+        let mut spawner = Spawner::from_config(&WastesConfig {
+            respawn: RespawnConfig::RateLimited { per_step: 0.5 },
+            ..Default::default()
+        });
+
+        let mut rng = rng();
+        let mut wastes = vec![Waste::new(na::Point2::new(0.5, 0.5), 0)];
+
+        assert!(!spawner.collected(&mut wastes[0], &mut rng));
+        assert!(!spawner.collected(&mut wastes[0], &mut rng));
+        wastes.clear();
+
+        let counts: Vec<_> = (0..6)
+            .map(|_| {
+                spawner.emit(&mut wastes, &mut rng);
+                wastes.len()
+            })
+            .collect();
+
+        assert_eq!(counts, [0, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_rivers() {
+        let mut spawner = Spawner::from_config(&WastesConfig {
+            respawn: RespawnConfig::None,
+            rivers: vec![RiverConfig { x: 0.0, y: 0.5, rate: 0.25, spread: 0.01 }],
+            max_count: 2,
+            ..Default::default()
+        });
+
+        let mut rng = rng();
+        let mut wastes = Vec::new();
+
+        assert!(!spawner.collected(&mut Waste::new(na::Point2::new(0.5, 0.5), 0), &mut rng));
+
+        for _ in 0..20 {
+            spawner.emit(&mut wastes, &mut rng);
+        }
+
+        assert_eq!(wastes.len(), 2);
+    }
+}
@@ -0,0 +1,599 @@
+use crate::*;
+use std::fmt;
+
+/// A field of a `SimulationConfig` holding a value the simulation cannot
+/// run with
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    /// Path to the field, e.g. `ga.selection.size`
+    pub field: String,
+
+    /// What the field should have been instead
+    pub expected: &'static str,
+}
+
+impl SimulationConfig {
+    /// Checks every value that would otherwise make the simulation panic
+    /// (or quietly produce NaNs) half-way through; called by the
+    /// `Simulation` constructors
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check("population_size", self.population_size > 0, "positive")?;
+        check_non_negative("collection_radius", self.collection_radius)?;
+
+        self.validate_collector()?;
+        self.validate_eye()?;
+        self.validate_ga()?;
+
+        if let SpatialIndexConfig::Grid { cells } = self.spatial_index {
+            check("spatial_index.cells", cells > 0, "positive")?;
+        }
+
+        if let EvaluationConfig::Isolated { batch_size } = self.evaluation {
+            check("evaluation.batch_size", batch_size > 0, "positive")?;
+        }
+
+        check("episodes.count", self.episodes.count > 0, "positive")?;
+
+        if let AggregationConfig::Quantile { quantile } = self.episodes.aggregation {
+            check_share("episodes.aggregation.quantile", quantile)?;
+        }
+
+        check_positive("fuel.capacity", self.fuel.capacity)?;
+        check_non_negative("fuel.refuel_rate", self.fuel.refuel_rate)?;
+        check("cargo.capacity", self.cargo.capacity > 0, "positive")?;
+        check_non_negative("ports.radius", self.ports.radius)?;
+
+        self.validate_currents()?;
+        self.validate_wastes()?;
+
+        for (id, polygon) in self.obstacles.polygons.iter().enumerate() {
+            check(
+                format!("obstacles.polygons[{}].points", id),
+                polygon.points.len() >= 3,
+                "at least 3 points",
+            )?;
+        }
+
+        check_non_negative("separation.radius", self.separation.radius)?;
+
+        self.validate_physics()
+    }
+
+    fn validate_collector(&self) -> Result<(), ConfigError> {
+        let collector = &self.collector;
+
+        check_non_negative("collector.speed_min", collector.speed_min)?;
+
+        check(
+            "collector.speed_max",
+            collector.speed_max >= collector.speed_min,
+            "at least `speed_min`",
+        )?;
+
+        check(
+            "collector.speed_initial",
+            (collector.speed_min..=collector.speed_max).contains(&collector.speed_initial),
+            "within `speed_min..=speed_max`",
+        )?;
+
+        check_non_negative("collector.speed_accel", collector.speed_accel)?;
+        check_non_negative("collector.rotation_accel", collector.rotation_accel)?;
+        check_non_negative("collector.energy_per_distance", collector.energy_per_distance)?;
+        check_non_negative("collector.energy_per_radian", collector.energy_per_radian)
+    }
+
+    fn validate_eye(&self) -> Result<(), ConfigError> {
+        let eye = &self.eye;
+
+        check_positive("eye.fov_range", eye.fov_range)?;
+        check_positive("eye.fov_angle", eye.fov_angle)?;
+        check("eye.cells", eye.cells > 0, "positive")?;
+
+        check_non_negative("eye.noise.std_dev", eye.noise.std_dev)?;
+        check_share("eye.noise.detection", eye.noise.detection)?;
+        check_share("eye.noise.dropout", eye.noise.dropout)?;
+        check_share("eye.noise.sea_state", eye.noise.sea_state)?;
+
+        if let SensorConfig::Rays { channels, collector_radius } = &eye.sensor {
+            check("eye.sensor.channels", !channels.is_empty(), "not empty")?;
+            check_non_negative("eye.sensor.collector_radius", *collector_radius)?;
+
+            for (id, channel) in channels.iter().enumerate() {
+                if let NormalizationConfig::Exponential { scale } = channel.normalization {
+                    check_positive(
+                        format!("eye.sensor.channels[{}].normalization.scale", id),
+                        scale,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_ga(&self) -> Result<(), ConfigError> {
+        if let SelectionConfig::Tournament { size } = self.ga.selection {
+            check("ga.selection.size", size > 0, "positive")?;
+        }
+
+        match self.ga.crossover {
+            CrossoverConfig::KPoint { points } => {
+                check("ga.crossover.points", points > 0, "positive")?;
+            }
+            CrossoverConfig::Blend { alpha } => {
+                check_non_negative("ga.crossover.alpha", alpha)?;
+            }
+            CrossoverConfig::SimulatedBinary { eta } => {
+                check_non_negative("ga.crossover.eta", eta)?;
+            }
+            _ => (),
+        }
+
+        match self.ga.mutation {
+            MutationConfig::Gaussian { chance, .. } => {
+                check_share("ga.mutation.chance", chance)?;
+            }
+            MutationConfig::Scheduled { chance, .. } => {
+                check_schedule("ga.mutation.chance", chance)?;
+            }
+            MutationConfig::SelfAdaptive { initial_step_size, min_step_size } => {
+                check_positive("ga.mutation.initial_step_size", initial_step_size)?;
+                check_non_negative("ga.mutation.min_step_size", min_step_size)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_currents(&self) -> Result<(), ConfigError> {
+        match &self.currents {
+            CurrentsConfig::Gyres { count, period, .. } => {
+                check("currents.count", *count > 0, "positive")?;
+
+                if let Some(period) = period {
+                    check_positive("currents.period", *period)?;
+                }
+            }
+
+            CurrentsConfig::Eddies { radius, .. } => {
+                check_positive("currents.radius", *radius)?;
+            }
+
+            CurrentsConfig::Grid { width, height, u, v } => {
+                check("currents.width", *width > 0, "positive")?;
+                check("currents.height", *height > 0, "positive")?;
+
+                let cells = width * height;
+                check("currents.u", u.len() == cells, "`width * height` values")?;
+                check("currents.v", v.len() == cells, "`width * height` values")?;
+            }
+
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn validate_wastes(&self) -> Result<(), ConfigError> {
+        let wastes = &self.wastes;
+
+        match &wastes.distribution {
+            DistributionConfig::Uniform => (),
+
+            DistributionConfig::Clusters { clusters } => {
+                for (id, cluster) in clusters.iter().enumerate() {
+                    check_non_negative(
+                        format!("wastes.distribution.clusters[{}].std_dev", id),
+                        cluster.std_dev,
+                    )?;
+                }
+
+                check_weights(
+                    "wastes.distribution.clusters",
+                    clusters.iter().map(|cluster| cluster.weight),
+                )?;
+            }
+
+            DistributionConfig::Raster { width, height, density } => {
+                check(
+                    "wastes.distribution.density",
+                    density.len() == width * height,
+                    "`width * height` values",
+                )?;
+
+                check_weights("wastes.distribution.density", density.iter().copied())?;
+            }
+        }
+
+        if let RespawnConfig::RateLimited { per_step } = wastes.respawn {
+            check_non_negative("wastes.respawn.per_step", per_step)?;
+        }
+
+        for (id, river) in wastes.rivers.iter().enumerate() {
+            check_non_negative(format!("wastes.rivers[{}].rate", id), river.rate)?;
+            check_non_negative(format!("wastes.rivers[{}].spread", id), river.spread)?;
+        }
+
+        check("wastes.categories", !wastes.categories.is_empty(), "not empty")?;
+
+        if wastes.categories.len() > 1 {
+            check_weights(
+                "wastes.categories",
+                wastes.categories.iter().map(|category| category.share),
+            )?;
+        }
+
+        for (id, category) in wastes.categories.iter().enumerate() {
+            if let Some(radius) = category.radius {
+                check_non_negative(format!("wastes.categories[{}].radius", id), radius)?;
+            }
+
+            check_non_negative(
+                format!("wastes.categories[{}].visibility", id),
+                category.visibility,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_physics(&self) -> Result<(), ConfigError> {
+        let physics = &self.physics;
+
+        check_positive("physics.mass", physics.mass)?;
+        check_non_negative("physics.thrust", physics.thrust)?;
+        check_non_negative("physics.linear_drag", physics.linear_drag)?;
+        check_non_negative("physics.angular_drag", physics.angular_drag)?;
+        check_non_negative("physics.max_turn_rate", physics.max_turn_rate)?;
+        check_positive("physics.timestep", physics.timestep)
+    }
+}
+
+fn check(
+    field: impl Into<String>,
+    valid: bool,
+    expected: &'static str,
+) -> Result<(), ConfigError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError {
+            field: field.into(),
+            expected,
+        })
+    }
+}
+
+// NaNs fail every comparison, so they are rejected along the way
+
+fn check_positive(field: impl Into<String>, value: f32) -> Result<(), ConfigError> {
+    check(field, value > 0.0, "positive")
+}
+
+fn check_non_negative(field: impl Into<String>, value: f32) -> Result<(), ConfigError> {
+    check(field, value >= 0.0, "zero or more")
+}
+
+fn check_share(field: impl Into<String>, value: f32) -> Result<(), ConfigError> {
+    check(field, (0.0..=1.0).contains(&value), "between 0.0 and 1.0")
+}
+
+/// Every value a schedule may take stays a valid share
+fn check_schedule(field: &str, schedule: ga::Schedule) -> Result<(), ConfigError> {
+    match schedule {
+        ga::Schedule::Constant(value) => check_share(field, value),
+
+        ga::Schedule::Exponential { initial, decay, min } => {
+            check_share(format!("{}.initial", field), initial)?;
+            check_share(format!("{}.decay", field), decay)?;
+            check_share(format!("{}.min", field), min)
+        }
+
+        ga::Schedule::Linear { initial, last, .. } => {
+            check_share(format!("{}.initial", field), initial)?;
+            check_share(format!("{}.last", field), last)
+        }
+    }
+}
+
+/// Weights of a random pick: none negative, at least one positive
+fn check_weights(
+    field: &str,
+    mut weights: impl Iterator<Item = f32> + Clone,
+) -> Result<(), ConfigError> {
+    check(
+        field,
+        weights.clone().all(|weight| weight >= 0.0 && weight.is_finite())
+            && weights.any(|weight| weight > 0.0),
+        "no negative weight and at least one positive",
+    )
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config: `{}` must be {}", self.field, self.expected)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejects(field: &str, tweak: impl FnOnce(&mut SimulationConfig)) {
+        let mut config = SimulationConfig::default();
+        tweak(&mut config);
+
+        assert_eq!(
+            config.validate().map_err(|err| err.field),
+            Err(field.to_string()),
+        );
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(SimulationConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_population_size() {
+        rejects("population_size", |config| config.population_size = 0);
+    }
+
+    #[test]
+    fn test_speed_range() {
+        rejects("collector.speed_max", |config| config.collector.speed_max = 0.0);
+    }
+
+    #[test]
+    fn test_speed_initial() {
+        rejects("collector.speed_initial", |config| config.collector.speed_initial = 0.0);
+        rejects("collector.speed_initial", |config| config.collector.speed_initial = 0.01);
+    }
+
+    #[test]
+    fn test_energy_per_distance() {
+        rejects("collector.energy_per_distance", |config| {
+            config.collector.energy_per_distance = -1.0;
+        });
+    }
+
+    #[test]
+    fn test_energy_per_radian() {
+        rejects("collector.energy_per_radian", |config| {
+            config.collector.energy_per_radian = -0.01;
+        });
+    }
+
+    #[test]
+    fn test_eye_cells() {
+        rejects("eye.cells", |config| config.eye.cells = 0);
+    }
+
+    #[test]
+    fn test_noise_std_dev() {
+        rejects("eye.noise.std_dev", |config| config.eye.noise.std_dev = -0.1);
+    }
+
+    #[test]
+    fn test_noise_dropout() {
+        rejects("eye.noise.dropout", |config| config.eye.noise.dropout = 1.5);
+    }
+
+    #[test]
+    fn test_sea_state() {
+        rejects("eye.noise.sea_state", |config| config.eye.noise.sea_state = -0.5);
+    }
+
+    #[test]
+    fn test_ray_channels() {
+        rejects("eye.sensor.channels", |config| {
+            config.eye.sensor = SensorConfig::Rays {
+                channels: Vec::new(),
+                collector_radius: 0.01,
+            };
+        });
+    }
+
+    #[test]
+    fn test_ray_collector_radius() {
+        rejects("eye.sensor.collector_radius", |config| {
+            config.eye.sensor = SensorConfig::Rays {
+                channels: RayChannelConfig::all(),
+                collector_radius: -0.01,
+            };
+        });
+    }
+
+    #[test]
+    fn test_tournament_size() {
+        rejects("ga.selection.size", |config| {
+            config.ga.selection = SelectionConfig::Tournament { size: 0 };
+        });
+    }
+
+    #[test]
+    fn test_k_point() {
+        rejects("ga.crossover.points", |config| {
+            config.ga.crossover = CrossoverConfig::KPoint { points: 0 };
+        });
+    }
+
+    #[test]
+    fn test_blend_alpha() {
+        rejects("ga.crossover.alpha", |config| {
+            config.ga.crossover = CrossoverConfig::Blend { alpha: -0.5 };
+        });
+    }
+
+    #[test]
+    fn test_mutation_chance() {
+        rejects("ga.mutation.chance", |config| {
+            config.ga.mutation = MutationConfig::Gaussian { chance: 1.5, coeff: 0.3 };
+        });
+    }
+
+    #[test]
+    fn test_mutation_schedule() {
+        rejects("ga.mutation.chance.initial", |config| {
+            config.ga.mutation = MutationConfig::Scheduled {
+                chance: ga::Schedule::Linear { initial: -0.1, last: 0.01, generations: 10 },
+                coeff: ga::Schedule::Constant(0.3),
+            };
+        });
+    }
+
+    #[test]
+    fn test_self_adaptive() {
+        rejects("ga.mutation.initial_step_size", |config| {
+            config.ga.mutation = MutationConfig::SelfAdaptive {
+                initial_step_size: 0.0,
+                min_step_size: 0.0,
+            };
+        });
+    }
+
+    #[test]
+    fn test_grid_cells() {
+        rejects("spatial_index.cells", |config| {
+            config.spatial_index = SpatialIndexConfig::Grid { cells: 0 };
+        });
+    }
+
+    #[test]
+    fn test_batch_size() {
+        rejects("evaluation.batch_size", |config| {
+            config.evaluation = EvaluationConfig::Isolated { batch_size: 0 };
+        });
+    }
+
+    #[test]
+    fn test_episodes() {
+        rejects("episodes.count", |config| config.episodes.count = 0);
+    }
+
+    #[test]
+    fn test_quantile() {
+        rejects("episodes.aggregation.quantile", |config| {
+            config.episodes.aggregation = AggregationConfig::Quantile { quantile: 2.0 };
+        });
+    }
+
+    #[test]
+    fn test_fuel_capacity() {
+        rejects("fuel.capacity", |config| config.fuel.capacity = 0.0);
+    }
+
+    #[test]
+    fn test_cargo_capacity() {
+        rejects("cargo.capacity", |config| config.cargo.capacity = 0);
+    }
+
+    #[test]
+    fn test_currents_grid() {
+        rejects("currents.u", |config| {
+            config.currents = CurrentsConfig::Grid {
+                width: 2,
+                height: 2,
+                u: vec![0.0; 3],
+                v: vec![0.0; 4],
+            };
+        });
+    }
+
+    #[test]
+    fn test_gyres_period() {
+        rejects("currents.period", |config| {
+            config.currents = CurrentsConfig::Gyres {
+                count: 1,
+                strength: 0.001,
+                period: Some(0.0),
+            };
+        });
+    }
+
+    #[test]
+    fn test_eddies() {
+        rejects("currents.radius", |config| {
+            config.currents = CurrentsConfig::Eddies {
+                count: 3,
+                radius: 0.0,
+                strength: 0.001,
+                seed: 0,
+                drift: 0.0,
+            };
+        });
+    }
+
+    #[test]
+    fn test_clusters() {
+        rejects("wastes.distribution.clusters", |config| {
+            config.wastes.distribution = DistributionConfig::Clusters {
+                clusters: vec![ClusterConfig { x: 0.5, y: 0.5, std_dev: 0.1, weight: 0.0 }],
+            };
+        });
+    }
+
+    #[test]
+    fn test_raster() {
+        rejects("wastes.distribution.density", |config| {
+            config.wastes.distribution = DistributionConfig::Raster {
+                width: 2,
+                height: 1,
+                density: vec![0.0, 0.0],
+            };
+        });
+    }
+
+    #[test]
+    fn test_categories() {
+        rejects("wastes.categories", |config| config.wastes.categories.clear());
+    }
+
+    #[test]
+    fn test_category_shares() {
+        rejects("wastes.categories", |config| {
+            config.wastes.categories = vec![
+                WasteCategoryConfig { share: -1.0, ..Default::default() },
+                WasteCategoryConfig { share: 2.0, ..Default::default() },
+            ];
+        });
+    }
+
+    #[test]
+    fn test_obstacles() {
+        rejects("obstacles.polygons[1].points", |config| {
+            config.obstacles.polygons = vec![
+                ObstacleConfig {
+                    kind: ObstacleKind::Island,
+                    points: vec![[0.1, 0.1], [0.2, 0.1], [0.2, 0.2]],
+                },
+                ObstacleConfig {
+                    kind: ObstacleKind::Island,
+                    points: vec![[0.5, 0.5], [0.6, 0.5]],
+                },
+            ];
+        });
+    }
+
+    #[test]
+    fn test_mass() {
+        rejects("physics.mass", |config| config.physics.mass = 0.0);
+    }
+
+    #[test]
+    fn test_timestep() {
+        rejects("physics.timestep", |config| config.physics.timestep = f32::NAN);
+    }
+
+    #[test]
+    fn test_display() {
+        let err = ConfigError {
+            field: "eye.cells".into(),
+            expected: "positive",
+        };
+
+        assert_eq!(err.to_string(), "invalid config: `eye.cells` must be positive");
+    }
+}
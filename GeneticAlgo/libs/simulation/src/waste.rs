@@ -3,16 +3,27 @@ use crate::*;
 #[derive(Debug)]
 pub struct Waste {
     pub(crate) position: na::Point2<f32>,
+    /// Index into `WastesConfig::categories`
+    pub(crate) category: usize,
 }
 
 impl Waste {
+    pub fn new(position: na::Point2<f32>, category: usize) -> Self {
+        Self { position, category }
+    }
+
     pub fn random(rng: &mut dyn RngCore) -> Self {
         Self {
             position: rng.gen(),
+            category: 0,
         }
     }
 
     pub fn position(&self) -> na::Point2<f32> {
         self.position
     }
+
+    pub fn category(&self) -> usize {
+        self.category
+    }
 }
@@ -8,6 +8,10 @@ document.getElementById('train').onclick = function() {
     }
 };
 
+document.getElementById('replay').onclick = function() {
+    simulation.replayChampion();
+};
+
 // Test Wasm binding
 const sea = simulation.sea();
 console.log(sea);
@@ -67,12 +71,39 @@ CanvasRenderingContext2D.prototype.drawCollector=
         this.fill();
     };
 
+CanvasRenderingContext2D.prototype.drawPort =
+    function(x, y, size) {
+        this.fillStyle = '#2e86c1';
+        this.fillRect(x - size, y - size, 2.0 * size, 2.0 * size);
+    };
+
+CanvasRenderingContext2D.prototype.drawObstacle =
+    function(points, kind, scale) {
+        this.beginPath();
+
+        for (const [x, y] of points) {
+            this.lineTo(x * scale, y * scale);
+        }
+
+        this.closePath();
+
+        if (kind === 'exclusion') {
+            this.strokeStyle = '#c0392b';
+            this.stroke();
+        } else {
+            this.fillStyle = kind === 'coastline' ? '#a9845b' : '#58a55c';
+            this.fill();
+        }
+    };
+
+const wasteColors = ['#f1c40f', '#27ae60', '#8e44ad', '#c0392b'];
+
 CanvasRenderingContext2D.prototype.drawCircle =
-    function(x, y, radius) {
+    function(x, y, radius, color = wasteColors[0]) {
         this.beginPath();
         this.arc(x, y, radius, 0, 2.0 * Math.PI);
 
-        this.fillStyle = '#f1c40f';
+        this.fillStyle = color;
         this.fill();
     };
 
@@ -80,16 +111,33 @@ function redraw() {
     ctxt.clearRect(0, 0, viewportWidth, viewportWidth);
 
     for (let i = 0; i < 1; i++) {
-        simulation.step();
+        const stats = simulation.step();
+
+        if (stats) {
+            addData(statChart, stats);
+        }
     }
 
     const sea = simulation.sea();
 
+    for (const obstacle of sea.obstacles) {
+        ctxt.drawObstacle(obstacle.points, obstacle.kind, viewportWidth);
+    }
+
+    for (const port of sea.ports) {
+        ctxt.drawPort(
+            port.x * viewportWidth,
+            port.y * viewportWidth,
+            0.015 * viewportWidth,
+        );
+    }
+
     for (const waste of sea.wastes) {
         ctxt.drawCircle(
             waste.x * viewportWidth,
             waste.y * viewportWidth,
             (0.01 / 2.0) * viewportWidth,
+            wasteColors[waste.category % wasteColors.length],
         );
     }
 
@@ -170,14 +218,7 @@ function removeData(chart) {
 }
 
 function train() {
-    let data = simulation.train();
-    const regex = /(\w+)=(\d+\.\d+)/g;
-    const matches = data.matchAll(regex);
-
-    const result = {};
-    for (const match of matches) {
-        result[match[1]] = parseFloat(match[2]);
-    }
-    addData(statChart, result)
-    return result;
+    const stats = simulation.train();
+    addData(statChart, stats);
+    return stats;
 }